@@ -0,0 +1,325 @@
+use std::ops::Range;
+use std::sync::OnceLock;
+use pulldown_cmark::{html, CowStr, Event, Parser, Tag};
+use regex::Regex;
+use crate::page::markdown_options;
+
+/// The callouts pages can use, with the CSS class suffix and default title of each
+const KINDS: [(&str, &str); 7] = [
+    ("note", "Note"),
+    ("tip", "Tip"),
+    ("important", "Important"),
+    ("warning", "Warning"),
+    ("caution", "Caution"),
+    ("danger", "Danger"),
+    ("deprecated", "Deprecated"),
+];
+
+/// A callout block opened by `> [!KIND]` or `:::kind`
+#[derive(Debug, Clone)]
+struct Callout {
+    kind: &'static str,
+    title: String,
+    /// `None` for a plain callout, otherwise whether a collapsible one starts expanded
+    expanded: Option<bool>,
+}
+
+impl Callout {
+    /// Parse `kind`, an optional `+` (collapsible, expanded) or `-` (collapsible, collapsed)
+    /// and an optional markdown title
+    fn parse(kind: &str, fold: &str, title: &str) -> Option<Self> {
+        let (kind, default_title) = KINDS.iter().find(|(name, _)| name.eq_ignore_ascii_case(kind))?;
+        let title = title.trim();
+
+        Some(Callout {
+            kind,
+            title: if title.is_empty() { default_title.to_string() } else { inline_html(title) },
+            expanded: match fold {
+                "+" => Some(true),
+                "-" => Some(false),
+                _ => None,
+            },
+        })
+    }
+
+    fn open_html(&self) -> String {
+        match self.expanded {
+            Some(expanded) => format!(
+                "<details class=\"admonition admonition-{}\"{}>\n<summary class=\"admonition-title\">{}</summary>\n",
+                self.kind,
+                if expanded { " open" } else { "" },
+                self.title
+            ),
+            None => format!(
+                "<div class=\"admonition admonition-{}\" role=\"note\">\n<p class=\"admonition-title\">{}</p>\n",
+                self.kind, self.title
+            ),
+        }
+    }
+
+    fn close_html(&self) -> &'static str {
+        match self.expanded {
+            Some(_) => "</details>\n",
+            None => "</div>\n",
+        }
+    }
+}
+
+/// A markdown body with its `:::` fence lines swapped for HTML comments. An HTML block ends
+/// any open paragraph or lazy list item, which is how a fence line should behave, and the
+/// comments come back out of the parser as [`Event::Html`] for [`render`] to replace.
+pub struct Fenced {
+    pub source: String,
+    /// One entry per marker: the callout it opens, or `None` for a closing fence
+    fences: Vec<Option<Callout>>,
+    /// Byte ranges of each replaced line in `source` and in the original body
+    replaced: Vec<(Range<usize>, Range<usize>)>,
+}
+
+impl Fenced {
+    pub fn new(body: &str) -> Self {
+        static FENCE: OnceLock<Regex> = OnceLock::new();
+        let fence = FENCE.get_or_init(|| Regex::new(r"^(:{3,})\s*(?:([A-Za-z]+)([+-]?)(?:\s+(.*))?)?$").unwrap());
+
+        let mut source = String::with_capacity(body.len());
+        let mut fences = Vec::new();
+        let mut replaced = Vec::new();
+        let mut code_fence: Option<String> = None;
+        let mut depth = 0;
+        let mut offset = 0;
+
+        for line in body.split_inclusive('\n') {
+            let content = line.trim_end_matches(['\n', '\r']);
+            let trimmed = content.trim_start();
+            let indent = content.len() - trimmed.len();
+
+            // Fences inside code blocks are code
+            if let Some(marker) = &code_fence {
+                if trimmed.starts_with(marker.as_str()) && trimmed.trim_start_matches(marker.as_bytes()[0] as char).trim().is_empty() {
+                    code_fence = None;
+                }
+            } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                let marker_char = trimmed.as_bytes()[0] as char;
+                code_fence = Some(trimmed.chars().take_while(|c| *c == marker_char).collect());
+            }
+
+            let callout = match fence.captures(trimmed) {
+                Some(caps) if code_fence.is_none() && indent < 4 => match caps.get(2) {
+                    Some(kind) => Callout::parse(kind.as_str(), &caps[3], caps.get(4).map_or("", |m| m.as_str())).map(Some),
+                    // A closing fence only counts while a callout is open
+                    None if depth > 0 => Some(None),
+                    None => None,
+                },
+                _ => None,
+            };
+
+            match callout {
+                Some(callout) => {
+                    depth = if callout.is_some() { depth + 1 } else { depth - 1 };
+                    let marker = format!("{}<!-- glowdoc-callout {} -->{}", &content[..indent], fences.len(), &line[content.len()..]);
+                    replaced.push((source.len()..source.len() + marker.len(), offset..offset + line.len()));
+                    source.push_str(&marker);
+                    fences.push(callout);
+                }
+                None => source.push_str(line),
+            }
+            offset += line.len();
+        }
+
+        Fenced { source, fences, replaced }
+    }
+
+    /// Offset in the original body of an offset into `source`
+    pub fn original_offset(&self, offset: usize) -> usize {
+        let index = self.replaced.partition_point(|(source, _)| source.start <= offset);
+        let Some((source, original)) = index.checked_sub(1).map(|i| &self.replaced[i]) else {
+            return offset;
+        };
+
+        if offset < source.end {
+            // Inside a marker, which stands for the whole fence line
+            original.start + (offset - source.start).min(original.len())
+        } else {
+            original.end + (offset - source.end)
+        }
+    }
+
+    /// The callout a marker comment opens or closes, if `html` is one
+    fn marker(&self, html: &str) -> Option<&Option<Callout>> {
+        let index = html.trim()
+            .strip_prefix("<!-- glowdoc-callout ")?
+            .strip_suffix(" -->")?
+            .parse::<usize>()
+            .ok()?;
+        self.fences.get(index)
+    }
+}
+
+/// Replace `:::` fence markers and `> [!KIND]` blockquotes with callout markup. `body` is the
+/// original markdown the event ranges point into.
+pub fn render(events: Vec<(Event<'static>, Range<usize>)>, fenced: &Fenced, body: &str) -> Vec<(Event<'static>, Range<usize>)> {
+    static ALERT: OnceLock<Regex> = OnceLock::new();
+    let alert = ALERT.get_or_init(|| Regex::new(r"^\[!([A-Za-z]+)\]([+-]?)(?:\s+(.*))?$").unwrap());
+
+    let mut output = Vec::with_capacity(events.len());
+    // Closing markup of the open fenced callouts, and of every open blockquote
+    let mut open_fences: Vec<&'static str> = Vec::new();
+    let mut open_quotes: Vec<Option<&'static str>> = Vec::new();
+
+    let mut i = 0;
+    while i < events.len() {
+        let (event, range) = &events[i];
+        match event {
+            Event::Html(html) => match fenced.marker(html) {
+                Some(Some(callout)) => {
+                    output.push((Event::Html(CowStr::from(callout.open_html())), range.clone()));
+                    open_fences.push(callout.close_html());
+                }
+                Some(None) => {
+                    if let Some(close) = open_fences.pop() {
+                        output.push((Event::Html(CowStr::Borrowed(close)), range.clone()));
+                    }
+                }
+                None => output.push((event.clone(), range.clone())),
+            },
+            Event::Start(Tag::BlockQuote) => {
+                // `> [!KIND] Title` on the first line of the quote's first paragraph
+                let callout = alert_line(&events, i, body).and_then(|(line, end)| {
+                    let caps = alert.captures(line)?;
+                    let callout = Callout::parse(&caps[1], &caps[2], caps.get(3).map_or("", |m| m.as_str()))?;
+                    Some((callout, end))
+                });
+
+                match callout {
+                    Some((callout, end)) => {
+                        output.push((Event::Html(CowStr::from(callout.open_html())), range.clone()));
+                        open_quotes.push(Some(callout.close_html()));
+                        // Drop the alert line, and its paragraph when nothing else is in it
+                        if !matches!(events[end].0, Event::End(Tag::Paragraph)) {
+                            output.push(events[i + 1].clone());
+                        }
+                        i = end + 1;
+                        continue;
+                    }
+                    None => {
+                        output.push((event.clone(), range.clone()));
+                        open_quotes.push(None);
+                    }
+                }
+            }
+            Event::End(Tag::BlockQuote) => match open_quotes.pop().flatten() {
+                Some(close) => output.push((Event::Html(CowStr::Borrowed(close)), range.clone())),
+                None => output.push((event.clone(), range.clone())),
+            },
+            _ => output.push((event.clone(), range.clone())),
+        }
+        i += 1;
+    }
+
+    // Callouts left open close with the page
+    let end = body.len()..body.len();
+    for close in open_fences.into_iter().rev() {
+        output.push((Event::Html(CowStr::Borrowed(close)), end.clone()));
+    }
+
+    output
+}
+
+/// The first line of the paragraph opening the blockquote at `start`, with the index of the
+/// event ending that line
+fn alert_line<'a>(events: &[(Event, Range<usize>)], start: usize, body: &'a str) -> Option<(&'a str, usize)> {
+    if !matches!(events.get(start + 1), Some((Event::Start(Tag::Paragraph), _))) {
+        return None;
+    }
+    let (first, first_range) = events.get(start + 2)?;
+    if !matches!(first, Event::Text(_)) {
+        return None;
+    }
+
+    let end = (start + 2..events.len())
+        .find(|&i| matches!(events[i].0, Event::SoftBreak | Event::HardBreak | Event::End(Tag::Paragraph)))?;
+    let line = body.get(first_range.start..events[end - 1].1.end)?;
+    Some((line.trim(), end))
+}
+
+/// Render a one-line markdown title without the paragraph around it
+fn inline_html(markdown: &str) -> String {
+    let mut rendered = String::new();
+    html::push_html(&mut rendered, Parser::new_ext(markdown, markdown_options()));
+    let rendered = rendered.trim();
+    rendered.strip_prefix("<p>")
+        .and_then(|inner| inner.strip_suffix("</p>"))
+        .unwrap_or(rendered)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use crate::page::ParsedPage;
+
+    fn to_html(markdown: &str) -> String {
+        let page = ParsedPage::from_source(Path::new("docs"), "page.md", markdown).unwrap();
+        let mut rendered = String::new();
+        html::push_html(&mut rendered, page.render_events());
+        rendered
+    }
+
+    #[test]
+    fn alert_blockquotes_become_callouts() {
+        assert_eq!(to_html("> [!WARNING] Mind the gap\n> Body text.\n"), "\
+<div class=\"admonition admonition-warning\" role=\"note\">
+<p class=\"admonition-title\">Mind the gap</p>
+<p>Body text.</p>
+</div>
+");
+        // Unknown kinds stay quotes
+        assert!(to_html("> [!SHRUG] Hm\n").starts_with("<blockquote>"));
+    }
+
+    #[test]
+    fn fences_with_a_plus_are_expanded_details() {
+        assert_eq!(to_html(":::tip+ Try *this*\nBody\n:::\n"), "\
+<details class=\"admonition admonition-tip\" open>
+<summary class=\"admonition-title\">Try <em>this</em></summary>
+<p>Body</p>
+</details>
+");
+    }
+
+    #[test]
+    fn fences_nest() {
+        assert_eq!(to_html(":::note Outer\nA\n::::warning Inner\nB\n::::\nC\n:::\n"), "\
+<div class=\"admonition admonition-note\" role=\"note\">
+<p class=\"admonition-title\">Outer</p>
+<p>A</p>
+<div class=\"admonition admonition-warning\" role=\"note\">
+<p class=\"admonition-title\">Inner</p>
+<p>B</p>
+</div>
+<p>C</p>
+</div>
+");
+    }
+
+    #[test]
+    fn fences_in_code_blocks_are_code() {
+        assert_eq!(to_html("```\n:::note\n```\n\n:::\n"), "<pre><code>:::note\n</code></pre>\n<p>:::</p>\n");
+    }
+
+    #[test]
+    fn offsets_after_a_replaced_line_map_to_the_original_body() {
+        let body = "Intro\n:::note\nText\n:::\nEnd\n";
+        let fenced = Fenced::new(body);
+        assert_ne!(fenced.source.len(), body.len());
+
+        for text in ["Intro", "Text", "End"] {
+            assert_eq!(fenced.original_offset(fenced.source.find(text).unwrap()), body.find(text).unwrap(), "{}", text);
+        }
+        // Offsets inside a marker stay within the fence line it replaced, newline included
+        let marker = fenced.source.find("<!--").unwrap();
+        assert_eq!(fenced.original_offset(marker), body.find(":::note").unwrap());
+        assert_eq!(fenced.original_offset(marker + 20), body.find("Text").unwrap());
+    }
+}
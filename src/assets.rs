@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::error::{self, Result};
+use crate::ignore::{Ignore, IGNORE_FILE};
+use crate::SiteFile;
+
+/// Folder inside the output directory that referenced files are copied to
+pub const ASSETS_DIR: &str = "assets";
+
+/// Length of the content hash in copied file names, e.g. `logo-3f9a2c1e.png`
+const HASH_LEN: usize = 8;
+
+/// Extensions of images, audio and video, and of files offered as downloads. A reference to
+/// one of these that does not exist fails the build; other local links are only warned about.
+const REQUIRED_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "webp", "avif", "ico", "bmp",
+    "mp4", "webm", "ogv", "mov", "mp3", "ogg", "wav", "m4a", "flac", "vtt",
+    "pdf", "zip", "tar", "gz", "tgz", "xz", "7z", "dmg", "exe", "msi", "deb", "rpm", "epub",
+];
+
+/// Files referenced from markdown (images, videos, downloads) that are copied into the output
+/// directory under content-hashed names
+#[derive(Debug)]
+pub struct Assets {
+    docs_path: PathBuf,
+    /// Files that are not published, which count as missing
+    ignore: Ignore,
+    /// Source file mapped to its name inside [`ASSETS_DIR`]
+    copied: Mutex<BTreeMap<PathBuf, String>>,
+    /// `file: 'url'` for every reference whose file does not exist
+    missing: Mutex<Vec<String>>,
+    /// `(file, url, reason)` for every link to a missing file that is not [required](is_required)
+    unresolved: Mutex<Vec<(String, String, String)>>,
+    /// Markdown file mapped to the `(url, path)` pairs it referenced, kept for the build cache.
+    /// The path is empty for a missing file that is not [required](is_required).
+    references: Mutex<HashMap<String, Vec<(String, String)>>>,
+}
+
+impl Assets {
+    pub fn new<P: AsRef<Path>>(docs_path: P, ignore: Ignore) -> Self {
+        Assets {
+            docs_path: docs_path.as_ref().to_path_buf(),
+            ignore,
+            copied: Mutex::new(BTreeMap::new()),
+            missing: Mutex::new(Vec::new()),
+            unresolved: Mutex::new(Vec::new()),
+            references: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record a reference to `url` from `from_file` (relative to the docs folder), returning
+    /// its path inside the output directory. Returns `None` for URLs that are not local files,
+    /// and for missing files, which are remembered for [`Assets::missing`] when they are
+    /// [required](is_required) and for [`Assets::unresolved`] otherwise.
+    pub fn register(&self, from_file: &str, url: &str) -> Option<String> {
+        let (path, suffix) = split_suffix(url);
+        let source = match resolve(&self.docs_path, &self.ignore, from_file, url)? {
+            Ok(source) => source,
+            Err(reason) if !is_required(url) => {
+                self.unresolved.lock().unwrap().push((from_file.to_string(), url.to_string(), reason));
+                self.record_reference(from_file, url, String::new());
+                return None;
+            }
+            Err(_) => {
+                self.missing.lock().unwrap().push(format!("{}: '{}'", from_file, url));
+                return None;
+            }
+        };
+
+        let mut copied = self.copied.lock().unwrap();
+        let name = match copied.get(&source) {
+            Some(name) => name.clone(),
+            None => {
+                let Ok(content) = fs::read(&source) else {
+                    self.missing.lock().unwrap().push(format!("{}: '{}'", from_file, url));
+                    return None;
+                };
+
+                let file_name = Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(path);
+                let name = hashed_name(file_name, &content);
+                copied.insert(source, name.clone());
+                name
+            }
+        };
+
+        let output_path = format!("{}/{}{}", ASSETS_DIR, name, suffix);
+        self.record_reference(from_file, url, output_path.clone());
+        Some(output_path)
+    }
+
+    fn record_reference(&self, from_file: &str, url: &str, output_path: String) {
+        let mut references = self.references.lock().unwrap();
+        let file_references = references.entry(from_file.to_string()).or_default();
+        if !file_references.iter().any(|(known, _)| known == url) {
+            file_references.push((url.to_string(), output_path));
+        }
+    }
+
+    /// The `(url, path)` pairs registered from `from_file`
+    pub fn references(&self, from_file: &str) -> Vec<(String, String)> {
+        self.references.lock().unwrap().get(from_file).cloned().unwrap_or_default()
+    }
+
+    /// Register the references of a page reused from the build cache. Returns `false` when a
+    /// file is gone, appeared or its content changed, in which case the page has to be
+    /// rendered again.
+    pub fn restore(&self, from_file: &str, references: &[(String, String)]) -> bool {
+        references.iter()
+            .all(|(url, path)| self.register(from_file, url).unwrap_or_default() == *path)
+    }
+
+    /// References to files that do not exist, as `file: 'url'`
+    pub fn missing(&self) -> Vec<String> {
+        // A cached page that is rendered again reports its missing files twice
+        let mut seen = HashSet::new();
+        self.missing.lock().unwrap().iter()
+            .filter(|reference| seen.insert(reference.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Links to missing files that do not fail the build, as `(file, url, reason)`
+    pub fn unresolved(&self) -> Vec<(String, String, String)> {
+        let mut seen = HashSet::new();
+        self.unresolved.lock().unwrap().iter()
+            .filter(|link| seen.insert(*link))
+            .cloned()
+            .collect()
+    }
+
+    /// Every registered file with its path inside the output directory
+    pub fn files(&self) -> Result<Vec<SiteFile>> {
+        let copied = self.copied.lock().unwrap();
+        copied.iter()
+            .map(|(source, name)| Ok(SiteFile {
+                path: format!("{}/{}", ASSETS_DIR, name),
+                content: fs::read(source).map_err(error::io(source))?,
+            }))
+            .collect()
+    }
+}
+
+/// Whether `name` looks like a copied file or search shard, used by `clean`
+pub fn is_hashed_name(name: &str) -> bool {
+    let stem = match name.rsplit_once('.') {
+        Some((stem, _)) => stem,
+        None => name,
+    };
+    stem.rsplit_once('-')
+        .is_some_and(|(_, hash)| hash.len() == HASH_LEN && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Whether a missing file behind `url` fails the build: images, audio, video and downloads,
+/// see [`REQUIRED_EXTENSIONS`]
+pub fn is_required(url: &str) -> bool {
+    let (path, _) = split_suffix(url);
+    Path::new(&path.to_ascii_lowercase())
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| REQUIRED_EXTENSIONS.contains(&extension))
+}
+
+/// Find the file behind a local URL referenced from `from_file`. Returns `None` when the URL
+/// is not a local file (external links, anchors, pages), and an error naming the URL when no
+/// file exists or the file is ignored.
+///
+/// Paths are tried relative to the markdown file, then to the docs folder. Paths starting with
+/// `/` are relative to the docs folder only.
+pub fn resolve(docs_path: &Path, ignore: &Ignore, from_file: &str, url: &str) -> Option<Result<PathBuf, String>> {
+    let (path, _) = split_suffix(url);
+    let lower = path.to_ascii_lowercase();
+    let is_local = !path.is_empty()
+        && !url.starts_with('#')
+        && !url.starts_with("//")
+        && !url.contains("://")
+        && !["mailto:", "tel:", "data:", "javascript:"].iter().any(|scheme| lower.starts_with(scheme));
+    let extension = Path::new(&lower).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if !is_local || extension.is_empty() || ["md", "html", "htm"].contains(&extension) {
+        return None;
+    }
+
+    let path = path.replace("%20", " ");
+    let from_dir = Path::new(from_file).parent().unwrap_or(Path::new(""));
+    let candidates = match path.strip_prefix('/') {
+        Some(path) => vec![docs_path.join(path)],
+        None => vec![docs_path.join(from_dir).join(&path), docs_path.join(&path)],
+    };
+
+    Some(match candidates.into_iter().find(|candidate| candidate.is_file()) {
+        Some(source) if ignore.is_ignored(&source) => {
+            Err(format!("file '{}' is excluded by {} or the `exclude` setting", url, IGNORE_FILE))
+        }
+        Some(source) => Ok(source),
+        None => Err(format!("file '{}' not found", url)),
+    })
+}
+
+/// Split `video.mp4#t=10` into the path and the query/fragment suffix
+fn split_suffix(url: &str) -> (&str, &str) {
+    match url.find(['?', '#']) {
+        Some(index) => url.split_at(index),
+        None => (url, ""),
+    }
+}
+
+/// `logo.png` becomes `logo-<hash>.png`
+fn hashed_name(file_name: &str, content: &[u8]) -> String {
+    let hash = short_hash(content);
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) => format!("{}-{}.{}", stem, hash, extension),
+        None => format!("{}-{}", file_name, hash),
+    }
+}
+
+/// The part of [`content_hash`] that goes into file names
+pub fn short_hash(content: &[u8]) -> String {
+    content_hash(content)[..HASH_LEN].to_string()
+}
+
+/// 64-bit FNV-1a hash as hex. Stable across builds and Rust versions, unlike `DefaultHasher`.
+pub fn content_hash(content: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_missing_media_and_downloads_fail_the_build() {
+        let root = std::env::temp_dir().join(format!("glowdoc-assets-{}", std::process::id()));
+        let docs = root.join("docs");
+        fs::create_dir_all(docs.join("guide/img")).unwrap();
+        fs::write(docs.join("guide/img/logo.png"), "png").unwrap();
+        fs::write(root.join("chart.svg"), "svg").unwrap();
+        let assets = Assets::new(&docs, Ignore::default());
+
+        let logo = assets.register("guide/setup.md", "img/logo.png");
+        let absolute = assets.register("entry.md", "/guide/img/logo.png");
+        // The folder containing the docs folder is not searched
+        let chart = assets.register("entry.md", "chart.svg");
+        let notes = assets.register("guide/setup.md", "notes.txt");
+        let references = assets.references("guide/setup.md");
+        let restored = Assets::new(&docs, Ignore::default()).restore("guide/setup.md", &references);
+        fs::remove_dir_all(&root).unwrap();
+
+        let logo = logo.unwrap();
+        assert!(logo.starts_with("assets/logo-") && logo.ends_with(".png"), "{}", logo);
+        assert_eq!(absolute, Some(logo.clone()));
+        assert_eq!(chart, None);
+        assert_eq!(assets.missing(), ["entry.md: 'chart.svg'"]);
+        assert_eq!(notes, None);
+        assert_eq!(assets.unresolved(), [("guide/setup.md".to_string(), "notes.txt".to_string(), "file 'notes.txt' not found".to_string())]);
+        assert_eq!(references, [("img/logo.png".to_string(), logo), ("notes.txt".to_string(), String::new())]);
+        assert!(restored);
+        assert_eq!(assets.register("guide/setup.md", "https://example.com/logo.png"), None);
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::assets;
+use crate::error::{self, Result};
+use crate::search::SearchRecord;
+use crate::HeaderItem;
+
+/// Folder in the output directory holding the caches of previous builds
+pub const CACHE_DIR: &str = ".glowdoc-cache";
+
+/// Rendered pages from previous builds, keyed by markdown file path and content hash, so that
+/// a rebuild only renders the files that changed
+#[derive(Debug, Default)]
+pub struct BuildCache {
+    /// Where the cache is saved, or `None` for a cache that only lives for one build
+    path: Option<PathBuf>,
+    pages: Mutex<HashMap<String, CachedPage>>,
+    /// Files looked up during this build; everything else is dropped when saving
+    used: Mutex<HashSet<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    pages: HashMap<String, CachedPage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedPage {
+    content_hash: String,
+    headers: Vec<HeaderItem>,
+    rendered: Option<CachedRender>,
+}
+
+/// Output of rendering one page, valid while the file content and the render context match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedRender {
+    /// Hash of what the HTML depends on besides the file, see [`context_hash`]
+    pub context_hash: String,
+    pub html: String,
+    /// Text of the page for the search index
+    pub search_records: Vec<SearchRecord>,
+    /// Asset references as `(url, path in the output)`, re-registered when the page is reused
+    pub assets: Vec<(String, String)>,
+    /// Links that were not rewritten, reported again when the page is reused
+    pub warnings: Vec<String>,
+}
+
+impl BuildCache {
+    /// Cache file for the site built from `config_path` into `output_dir`. Separate configs
+    /// get separate files, so doc sets built into the same directory do not evict each other.
+    pub fn path_for(output_dir: &str, config_path: &str) -> PathBuf {
+        let hash = assets::content_hash(config_path.as_bytes());
+        Path::new(output_dir).join(CACHE_DIR).join(format!("{}.json", &hash[..8]))
+    }
+
+    /// Load the cache saved at `path`. A missing, unreadable or outdated file starts empty.
+    pub fn load(path: PathBuf) -> Self {
+        let pages = fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|file| file.version == cache_version())
+            .map(|file| file.pages)
+            .unwrap_or_default();
+
+        BuildCache {
+            path: Some(path),
+            pages: Mutex::new(pages),
+            used: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Headers of `file`, if its content has not changed
+    pub fn headers(&self, file: &str, content_hash: &str) -> Option<Vec<HeaderItem>> {
+        self.used.lock().unwrap().insert(file.to_string());
+        let pages = self.pages.lock().unwrap();
+        pages.get(file)
+            .filter(|page| page.content_hash == content_hash)
+            .map(|page| page.headers.clone())
+    }
+
+    /// Remember the headers of `file`, dropping what was cached for older content
+    pub fn store_headers(&self, file: &str, content_hash: &str, headers: &[HeaderItem]) {
+        self.used.lock().unwrap().insert(file.to_string());
+        let mut pages = self.pages.lock().unwrap();
+        match pages.get_mut(file) {
+            Some(page) if page.content_hash == content_hash => page.headers = headers.to_vec(),
+            _ => {
+                pages.insert(file.to_string(), CachedPage {
+                    content_hash: content_hash.to_string(),
+                    headers: headers.to_vec(),
+                    rendered: None,
+                });
+            }
+        }
+    }
+
+    /// The rendered page for `file`, if neither its content nor its render context changed
+    pub fn rendered(&self, file: &str, content_hash: &str, context_hash: &str) -> Option<CachedRender> {
+        self.used.lock().unwrap().insert(file.to_string());
+        let pages = self.pages.lock().unwrap();
+        pages.get(file)
+            .filter(|page| page.content_hash == content_hash)
+            .and_then(|page| page.rendered.clone())
+            .filter(|rendered| rendered.context_hash == context_hash)
+    }
+
+    pub fn store_rendered(&self, file: &str, content_hash: &str, rendered: CachedRender) {
+        self.used.lock().unwrap().insert(file.to_string());
+        let mut pages = self.pages.lock().unwrap();
+        match pages.get_mut(file) {
+            Some(page) if page.content_hash == content_hash => page.rendered = Some(rendered),
+            _ => {
+                pages.insert(file.to_string(), CachedPage {
+                    content_hash: content_hash.to_string(),
+                    headers: Vec::new(),
+                    rendered: Some(rendered),
+                });
+            }
+        }
+    }
+
+    /// Write the entries used by this build to disk. Pages removed from the site are dropped.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let used = self.used.lock().unwrap();
+        let pages = self.pages.lock().unwrap().iter()
+            .filter(|(file, _)| used.contains(*file))
+            .map(|(file, page)| (file.clone(), page.clone()))
+            .collect();
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(error::io(dir))?;
+        }
+        let file = CacheFile { version: cache_version(), pages };
+        let writer = io::BufWriter::new(fs::File::create(path).map_err(error::io(path))?);
+        serde_json::to_writer(writer, &file).map_err(|e| error::io(path)(e.into()))?;
+
+        Ok(())
+    }
+}
+
+/// Cache files written by another build of GlowDoc are ignored, since rendering may have
+/// changed. The executable's size and modification time tell development builds apart.
+fn cache_version() -> String {
+    let executable = std::env::current_exe()
+        .and_then(fs::metadata)
+        .map(|metadata| format!("{}-{:?}", metadata.len(), metadata.modified().ok()))
+        .unwrap_or_default();
+    format!("{}-{}", env!("CARGO_PKG_VERSION"), assets::content_hash(executable.as_bytes()))
+}
+
+/// Hash of everything besides the file content that a rendered page depends on, such as the
+/// output mode, the page's location and the routes its links are rewritten to
+pub fn context_hash(parts: &[&str]) -> String {
+    assets::content_hash(parts.join("\n").as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GlowDocBuilder, OutputMode};
+
+    const CONFIG: &str = "\
+title: T
+description: D
+output_mode: multi-page
+navigation:
+  - title: Guide
+    id: guide
+    items:
+      - title: Setup
+        id: setup
+        file: guide/setup.md
+      - title: Usage
+        id: usage
+        file: guide/usage.md
+";
+
+    /// A multi-page site whose setup page includes `snippets/install.txt`
+    fn site(name: &str) -> (PathBuf, GlowDocBuilder) {
+        let root = std::env::temp_dir().join(format!("glowdoc-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let docs = root.join("docs");
+        fs::create_dir_all(docs.join("guide")).unwrap();
+        fs::create_dir_all(docs.join("snippets")).unwrap();
+        fs::write(docs.join("config.yaml"), CONFIG).unwrap();
+        fs::write(docs.join("entry.md"), "# Home\n").unwrap();
+        fs::write(docs.join("guide/setup.md"), "# Setup\n\n{{#include ../snippets/install.txt}}\n").unwrap();
+        fs::write(docs.join("guide/usage.md"), "# Usage\n").unwrap();
+        fs::write(docs.join("snippets/install.txt"), "Run the installer.\n").unwrap();
+
+        let builder = GlowDocBuilder::new(docs.to_str().unwrap()).with_output_dir(root.join("out").to_str().unwrap());
+        (root, builder)
+    }
+
+    /// Render `builder`'s site through its cache file and save the cache. Returns the pages
+    /// whose HTML came from the cache after [`poison`].
+    fn cached_pages(builder: &GlowDocBuilder) -> Vec<String> {
+        let cache = BuildCache::load(BuildCache::path_for(builder.output_dir(), builder.config_path()));
+        let site = builder.render_site(false, &cache).unwrap();
+        cache.save().unwrap();
+        let mut pages: Vec<String> = site.pages.iter()
+            .filter(|page| page.html.contains("POISONED"))
+            .map(|page| page.path.clone())
+            .collect();
+        pages.sort();
+        pages
+    }
+
+    /// Replace every rendered page in the cache file with a marker, so reused pages show
+    fn poison(builder: &GlowDocBuilder) {
+        let path = BuildCache::path_for(builder.output_dir(), builder.config_path());
+        let mut file: CacheFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        for page in file.pages.values_mut() {
+            if let Some(rendered) = &mut page.rendered {
+                rendered.html = "<p>POISONED</p>".to_string();
+            }
+        }
+        fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn unchanged_pages_are_reused_and_edited_pages_rendered_again() {
+        let (root, builder) = site("edit");
+        let docs = Path::new(builder.docs_path()).to_path_buf();
+
+        assert!(cached_pages(&builder).is_empty());
+        poison(&builder);
+        let unchanged = cached_pages(&builder);
+
+        poison(&builder);
+        fs::write(docs.join("guide/usage.md"), "# Usage\n\nMore.\n").unwrap();
+        let page_edited = cached_pages(&builder);
+
+        poison(&builder);
+        fs::write(docs.join("snippets/install.txt"), "Run the new installer.\n").unwrap();
+        let include_edited = cached_pages(&builder);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(unchanged, ["guide/setup/index.html", "guide/usage/index.html", "index.html"]);
+        assert_eq!(page_edited, ["guide/setup/index.html", "index.html"]);
+        assert_eq!(include_edited, ["guide/usage/index.html", "index.html"]);
+    }
+
+    #[test]
+    fn context_changes_render_every_page_again() {
+        let (root, builder) = site("context");
+        let docs = Path::new(builder.docs_path()).to_path_buf();
+        cached_pages(&builder);
+
+        // The output mode is part of every page's context
+        poison(&builder);
+        let single_file = builder.clone().with_output_mode(OutputMode::SingleFile);
+        let mode_changed = cached_pages(&single_file);
+
+        // So are the routes links are rewritten to, which a new page changes
+        cached_pages(&builder);
+        poison(&builder);
+        fs::write(docs.join("guide/faq.md"), "# FAQ\n").unwrap();
+        fs::write(docs.join("config.yaml"), format!("{}      - title: FAQ\n        id: faq\n        file: guide/faq.md\n", CONFIG)).unwrap();
+        let routes_changed = cached_pages(&builder);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(mode_changed.is_empty(), "{:?}", mode_changed);
+        assert!(routes_changed.is_empty(), "{:?}", routes_changed);
+    }
+
+    #[test]
+    fn unreadable_or_outdated_cache_files_start_empty() {
+        let (root, builder) = site("corrupt");
+        let path = BuildCache::path_for(builder.output_dir(), builder.config_path());
+        cached_pages(&builder);
+
+        fs::write(&path, "{\"version\": ").unwrap();
+        let corrupt = cached_pages(&builder);
+        let corrupt_site = builder.render().unwrap();
+
+        poison(&builder);
+        let mut file: CacheFile = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        file.version = "0.0.0-old".to_string();
+        fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+        let outdated = cached_pages(&builder);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(corrupt.is_empty());
+        assert!(outdated.is_empty());
+        assert!(corrupt_site.pages.iter().any(|page| page.html.contains("Run the installer.")));
+    }
+
+    #[test]
+    fn separate_configs_get_separate_cache_files() {
+        assert_eq!(BuildCache::path_for("out", "docs/config.yaml"), BuildCache::path_for("out", "docs/config.yaml"));
+        assert_ne!(BuildCache::path_for("out", "docs/config.yaml"), BuildCache::path_for("out", "docs/api.yaml"));
+        assert!(BuildCache::path_for("out", "docs/config.yaml").starts_with(Path::new("out").join(CACHE_DIR)));
+    }
+}
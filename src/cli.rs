@@ -0,0 +1,364 @@
+use glowdoc::config_builder::ConfigBuilder;
+use glowdoc::{Error, PathOptions, Verbosity};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Exit codes shared by every command. Failures with a known cause get their own code, so CI
+/// can tell a broken config from a broken link.
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_CONFIG: i32 = 3;
+pub const EXIT_LINKS: i32 = 4;
+pub const EXIT_PAGE: i32 = 5;
+pub const EXIT_PLUGIN: i32 = 6;
+
+/// Exit code for an error returned by a command. `check` reports the most basic kind of
+/// problem it found: the config, then preprocessors, pages and links.
+pub fn exit_code(error: &Error) -> i32 {
+    match error {
+        Error::Config { .. } => EXIT_CONFIG,
+        Error::Links { .. } | Error::Assets { .. } => EXIT_LINKS,
+        Error::Markdown { .. } => EXIT_PAGE,
+        Error::Plugin { .. } => EXIT_PLUGIN,
+        Error::Usage { .. } => EXIT_USAGE,
+        Error::Check { config, plugins, pages, links, .. } => {
+            [(config.is_empty(), EXIT_CONFIG), (plugins.is_empty(), EXIT_PLUGIN), (pages.is_empty(), EXIT_PAGE), (links.is_empty(), EXIT_LINKS)]
+                .into_iter()
+                .find(|(none, _)| !none)
+                .map_or(EXIT_FAILURE, |(_, code)| code)
+        }
+        Error::Io { .. } | Error::SearchIndex { .. } | Error::Server { .. } => EXIT_FAILURE,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Build,
+    Watch,
+    Serve,
+    Check,
+    Clean,
+    InitConfig,
+    Help,
+}
+
+impl Command {
+    const ALL: [Command; 7] = [
+        Command::Build,
+        Command::Watch,
+        Command::Serve,
+        Command::Check,
+        Command::Clean,
+        Command::InitConfig,
+        Command::Help,
+    ];
+
+    fn from_name(name: &str) -> Option<Command> {
+        Self::ALL.into_iter().find(|command| command.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Build => "build",
+            Command::Watch => "watch",
+            Command::Serve => "serve",
+            Command::Check => "check",
+            Command::Clean => "clean",
+            Command::InitConfig => "init-config",
+            Command::Help => "help",
+        }
+    }
+
+    fn summary(&self) -> &'static str {
+        match self {
+            Command::Build => "Build the site once (the default command)",
+            Command::Watch => "Build, serve on http://localhost:8000 and rebuild on changes",
+            Command::Serve => "Serve the built site on http://localhost:8000 without rebuilding",
+            Command::Check => "Check the config, pages and internal links without writing output",
+            Command::Clean => "Remove the files written by build",
+            Command::InitConfig => "Generate config.yaml from the docs folder",
+            Command::Help => "Show help for a command",
+        }
+    }
+
+    /// Message printed when the command returns an error
+    pub fn failure_label(&self) -> &'static str {
+        match self {
+            Command::Build => "Build failed",
+            Command::Watch => "Development server failed",
+            Command::Serve => "Server failed",
+            Command::Check => "Check failed",
+            Command::Clean => "Clean failed",
+            Command::InitConfig => "Config generation failed",
+            Command::Help => "Help failed",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub paths: PathOptions,
+    pub verbosity: Verbosity,
+    /// Fail builds on warnings
+    pub strict: bool,
+    /// `--help` was given for the command
+    pub help: bool,
+    pub version: bool,
+    /// Command the `help` command was asked about
+    pub help_topic: Option<Command>,
+    /// Options passed through to the command, such as those of `init-config`
+    pub args: Vec<String>,
+}
+
+impl Cli {
+    /// Parse command-line arguments (without the program name). Global options may appear
+    /// before or after the command.
+    pub fn parse(args: &[String]) -> Result<Cli, Error> {
+        let mut cli = Cli {
+            command: Command::Build,
+            paths: PathOptions::default(),
+            verbosity: Verbosity::Normal,
+            strict: false,
+            help: false,
+            version: false,
+            help_topic: None,
+            args: Vec::new(),
+        };
+        let mut command = None;
+        let mut i = 0;
+
+        while i < args.len() {
+            let arg = args[i].as_str();
+            match arg {
+                "--root" | "--config" | "--out" => {
+                    i += 1;
+                    if i >= args.len() {
+                        return Err(Error::usage(format!("{} requires a path", arg)));
+                    }
+                    let value = Some(args[i].clone());
+                    match arg {
+                        "--root" => cli.paths.root = value,
+                        "--config" => cli.paths.config = value,
+                        _ => cli.paths.out = value,
+                    }
+                }
+                "-v" | "--verbose" => cli.verbosity = Verbosity::Verbose,
+                "-q" | "--quiet" => cli.verbosity = Verbosity::Quiet,
+                "--strict" => cli.strict = true,
+                "-h" | "--help" => cli.help = true,
+                "-V" | "--version" => cli.version = true,
+                _ if command.is_none() && !arg.starts_with('-') => {
+                    command = Some(Command::from_name(arg).ok_or_else(|| Self::unknown_command(arg))?);
+                }
+                _ => match command {
+                    // init-config validates its own options
+                    Some(Command::InitConfig) => cli.args.push(args[i].clone()),
+                    Some(Command::Help) if cli.help_topic.is_none() && !arg.starts_with('-') => {
+                        cli.help_topic = Some(Command::from_name(arg).ok_or_else(|| Self::unknown_command(arg))?);
+                    }
+                    Some(command) => {
+                        return Err(Error::usage(format!("Unknown option '{}' for '{}'", arg, command.name())));
+                    }
+                    None => return Err(Error::usage(format!("Unknown option '{}'", arg))),
+                },
+            }
+            i += 1;
+        }
+
+        if let Some(command) = command {
+            cli.command = command;
+        }
+
+        // Report bad init-config options as usage errors before anything runs
+        if cli.command == Command::InitConfig && !cli.help {
+            ConfigBuilder::parse_options(&cli.args)?;
+        }
+
+        Ok(cli)
+    }
+
+    fn unknown_command(name: &str) -> Error {
+        let suggestion = Command::ALL
+            .iter()
+            .map(|command| command.name())
+            .find(|candidate| edit_distance(name, candidate) <= 2)
+            .map(|candidate| format!("\n\nDid you mean '{}'?", candidate))
+            .unwrap_or_default();
+
+        Error::usage(format!("Unknown command '{}'{}", name, suggestion))
+    }
+}
+
+/// Levenshtein distance, used to suggest a command for a typo
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Print the overview of all commands
+pub fn print_help() {
+    println!("GlowDoc {} - Modern Documentation Generator", VERSION);
+    println!("==========================================");
+    println!();
+    println!("USAGE:");
+    println!("    glowdoc [COMMAND] [OPTIONS]");
+    println!();
+    println!("COMMANDS:");
+    for command in Command::ALL {
+        println!("    {:<15}{}", command.name(), command.summary());
+    }
+    println!();
+    print_global_options();
+    println!();
+    println!("EXIT CODES:");
+    println!("    {}  Success", EXIT_SUCCESS);
+    println!("    {}  The command failed for another reason (a file could not be read or written, ...)", EXIT_FAILURE);
+    println!("    {}  Invalid command or options", EXIT_USAGE);
+    println!("    {}  The config cannot be loaded or has problems", EXIT_CONFIG);
+    println!("    {}  Broken links, or files referenced from pages that do not exist", EXIT_LINKS);
+    println!("    {}  A page cannot be read or parsed (front matter, includes)", EXIT_PAGE);
+    println!("    {}  A preprocessor failed", EXIT_PLUGIN);
+    println!();
+    println!("Run 'glowdoc <COMMAND> --help' for more information on a command.");
+}
+
+fn print_global_options() {
+    println!("GLOBAL OPTIONS:");
+    println!("    --root <DIR>                       Docs folder (default: docs, or 'root' in the config)");
+    println!("    --config <FILE>                    Config file (default: <root>/config.yaml)");
+    println!("    --out <DIR>                        Output directory (default: ., or 'out' in the config)");
+    println!("    -v, --verbose                      Print detailed progress (same as GLOWDOC_DEBUG=1)");
+    println!("    -q, --quiet                        Only print errors");
+    println!("    --strict                           Fail on warnings: missing page files, unknown themes,");
+    println!("                                       links that cannot be resolved (build, watch)");
+    println!("    -h, --help                         Show help");
+    println!("    -V, --version                      Show the version");
+}
+
+/// Print help for a single command
+pub fn print_command_help(command: Command) {
+    match command {
+        Command::Help => print_help(),
+        Command::InitConfig => ConfigBuilder::print_help(),
+        _ => {
+            println!("glowdoc {} - {}", command.name(), command.summary());
+            println!();
+            println!("USAGE:");
+            println!("    glowdoc {} [OPTIONS]", command.name());
+            println!();
+            match command {
+                Command::Build => {
+                    println!("Renders every page listed in the config and writes index.html (plus one");
+                    println!("file per page when output_mode is multi-page) to the output directory.");
+                    println!("With --strict, warnings fail the build instead of being printed.");
+                }
+                Command::Watch => {
+                    println!("Builds the site with hot reload, serves it on http://localhost:8000 and");
+                    println!("rebuilds whenever a file in the docs folder changes.");
+                }
+                Command::Serve => {
+                    println!("Serves the output directory on http://localhost:8000. Run 'glowdoc build'");
+                    println!("first; the site is not rebuilt when files change.");
+                }
+                Command::Check => {
+                    println!("Loads the config and every page without writing output, and checks each");
+                    println!("internal link in the pages and entry.md: '#section/page' links must name a");
+                    println!("page in the navigation, '#section/page#heading' and '#heading' links an");
+                    println!("existing heading. Problems are reported with their file and line, and the");
+                    println!("command exits with status {} for config problems, {} for broken links, {} for", EXIT_CONFIG, EXIT_LINKS, EXIT_PAGE);
+                    println!("pages that cannot be parsed and {} for a failing preprocessor.", EXIT_PLUGIN);
+                }
+                Command::Clean => {
+                    println!("Removes index.html, the per-page files of the multi-page output mode and");
+                    println!("the copied favicon from the output directory.");
+                }
+                Command::InitConfig | Command::Help => unreachable!(),
+            }
+            println!();
+            print_global_options();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glowdoc::{BrokenLink, Diagnostic, Severity};
+
+    fn parse(args: &[&str]) -> Result<Cli, Error> {
+        Cli::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    fn usage_message(result: Result<Cli, Error>) -> String {
+        match result {
+            Err(Error::Usage { message }) => message,
+            other => panic!("expected a usage error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn check_exits_with_the_most_basic_problem() {
+        let diagnostic = || Diagnostic { severity: Severity::Error, position: Some((1, 1)), message: "bad".to_string() };
+        let plugin = || Error::Plugin { name: "toc".to_string(), command: "toc".to_string(), message: "failed".to_string() };
+        let page = || Error::Markdown { path: "intro.md".into(), message: "bad front matter".to_string() };
+        let link = || BrokenLink { file: "intro.md".to_string(), line: 3, target: "#gone".to_string(), reason: "not found".to_string() };
+        let check = |config: bool, plugins: bool, pages: bool, links: bool| Error::Check {
+            config: if config { vec![diagnostic()] } else { Vec::new() },
+            plugins: if plugins { vec![plugin()] } else { Vec::new() },
+            pages: if pages { vec![page()] } else { Vec::new() },
+            links: if links { vec![link()] } else { Vec::new() },
+            warnings: Vec::new(),
+        };
+
+        assert_eq!(exit_code(&check(true, true, true, true)), EXIT_CONFIG);
+        assert_eq!(exit_code(&check(false, true, true, true)), EXIT_PLUGIN);
+        assert_eq!(exit_code(&check(false, false, true, true)), EXIT_PAGE);
+        assert_eq!(exit_code(&check(false, false, false, true)), EXIT_LINKS);
+        assert_eq!(exit_code(&check(false, false, false, false)), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn global_options_go_before_or_after_the_command() {
+        for args in [["--strict", "build"], ["build", "--strict"]] {
+            let cli = parse(&args).unwrap();
+            assert_eq!(cli.command, Command::Build);
+            assert!(cli.strict);
+        }
+
+        let cli = parse(&["--strict"]).unwrap();
+        assert_eq!(cli.command, Command::Build);
+        assert!(cli.strict);
+        assert!(!parse(&["check"]).unwrap().strict);
+    }
+
+    #[test]
+    fn unknown_options_are_usage_errors() {
+        assert_eq!(usage_message(parse(&["--fast"])), "Unknown option '--fast'");
+        assert_eq!(usage_message(parse(&["check", "--fast"])), "Unknown option '--fast' for 'check'");
+        assert_eq!(usage_message(parse(&["build", "--out"])), "--out requires a path");
+    }
+
+    #[test]
+    fn unknown_commands_suggest_a_close_one() {
+        assert_eq!(usage_message(parse(&["biuld"])), "Unknown command 'biuld'\n\nDid you mean 'build'?");
+        assert_eq!(usage_message(parse(&["help", "chek"])), "Unknown command 'chek'\n\nDid you mean 'check'?");
+        assert_eq!(usage_message(parse(&["deploy"])), "Unknown command 'deploy'");
+
+        assert_eq!(edit_distance("serve", "serve"), 0);
+        assert_eq!(edit_distance("sevre", "serve"), 2);
+        assert_eq!(edit_distance("", "clean"), 5);
+    }
+}
@@ -1,9 +1,14 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::io::{self, Write};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use serde::{Deserialize, Serialize};
 use pulldown_cmark::{Parser, Event, Tag, HeadingLevel};
+use crate::error::{self, Error, Result};
+use crate::frontmatter::{self, FrontMatter};
+use crate::ignore::Ignore;
+use crate::{Config, NavigationItem, NavigationSection, OutputMode, SocialLinks};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DetectedItem {
@@ -13,6 +18,9 @@ pub struct DetectedItem {
     pub original_filename: String,
     #[serde(default)]
     pub items: Vec<DetectedItem>,
+    /// Front matter of the page, used to order pages before the config is written
+    #[serde(skip)]
+    pub front_matter: FrontMatter,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,46 +36,27 @@ pub struct DetectedConfig {
     pub title: String,
     pub description: String,
     pub navigation: Vec<DetectedSection>,
+    /// Page files left out because they cannot be read or their front matter is invalid,
+    /// with the reason
+    #[serde(skip)]
+    pub skipped: Vec<(PathBuf, String)>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ConfigItem {
-    pub title: String,
-    pub id: String,
-    pub file: Option<String>,
-    #[serde(default)]
-    pub items: Vec<ConfigItem>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ConfigSection {
-    pub title: String,
-    pub id: String,
-    pub items: Vec<ConfigItem>,
+/// Metadata of a folder in its `_section.yaml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FolderMeta {
+    title: Option<String>,
+    id: Option<String>,
+    order: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Config {
-    pub title: String,
-    pub description: String,
-    pub navigation: Vec<ConfigSection>,
-    #[serde(default)]
-    pub social: SocialLinks,
-}
-
-#[derive(Debug, Serialize, Deserialize, Default, Clone)]
-pub struct SocialLinks {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub instagram: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub github: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub mastodon: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub threads: Option<String>,
-}
+/// File with the title, ID and `order` of the folder it is in
+const SECTION_FILE: &str = "_section.yaml";
+/// Page listed first in its folder, whose front matter `title` and `order` apply to the folder
+const INDEX_FILE: &str = "index.md";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ConfigOptions {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -77,36 +66,85 @@ pub struct ConfigOptions {
     pub page_orders: HashMap<String, Vec<String>>, // "section" -> ["file1", "file2"]
     pub exclude_sections: Vec<String>,
     pub social: SocialLinks,
+    /// Update the existing config with [`ConfigBuilder::sync_config`] instead of regenerating it
+    pub sync: bool,
+    /// Remove pages whose files were deleted when syncing, instead of only reporting them
+    pub prune: bool,
+    /// Print the changes to the config file as a diff instead of writing it
+    pub dry_run: bool,
+    /// Let `--sync` rewrite a config file that has comments, which are lost
+    pub discard_comments: bool,
 }
 
-impl Default for ConfigOptions {
-    fn default() -> Self {
-        Self {
-            title: None,
-            description: None,
-            section_order: None,
-            section_renames: HashMap::new(),
-            page_renames: HashMap::new(),
-            page_orders: HashMap::new(),
-            exclude_sections: Vec::new(),
-            social: SocialLinks::default(),
+impl ConfigOptions {
+    /// Whether any option customizes the generated config
+    pub fn customizes(&self) -> bool {
+        self.title.is_some()
+            || self.description.is_some()
+            || self.section_order.is_some()
+            || !self.section_renames.is_empty()
+            || !self.page_renames.is_empty()
+            || !self.page_orders.is_empty()
+            || !self.exclude_sections.is_empty()
+            || self.social.instagram.is_some()
+            || self.social.github.is_some()
+            || self.social.mastodon.is_some()
+            || self.social.threads.is_some()
+    }
+}
+
+/// A change [`ConfigBuilder::sync_config`] makes to the navigation, by page file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncChange {
+    /// A file that was not in the navigation was added to it
+    Added(String),
+    /// The file of a page no longer exists; the page is kept
+    Missing(String),
+    /// The page of a deleted file was removed
+    Removed(String),
+}
+
+impl fmt::Display for SyncChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyncChange::Added(file) => write!(f, "➕ Added {}", file),
+            SyncChange::Missing(file) => write!(f, "⚠️  {} no longer exists; run with --prune to remove its page", file),
+            SyncChange::Removed(file) => write!(f, "➖ Removed {}", file),
         }
     }
 }
 
+
 pub struct ConfigBuilder {
     docs_path: PathBuf,
+    config_path: PathBuf,
+    /// `exclude:` globs of the config, applied with `.glowdocignore` when scanning the docs folder
+    exclude: Vec<String>,
 }
 
 impl ConfigBuilder {
     pub fn new<P: AsRef<Path>>(docs_path: P) -> Self {
         Self {
             docs_path: docs_path.as_ref().to_path_buf(),
+            config_path: docs_path.as_ref().join("config.yaml"),
+            exclude: Vec::new(),
         }
     }
     
+    /// Write the config somewhere other than `config.yaml` in the docs folder
+    pub fn with_config_path<P: AsRef<Path>>(mut self, config_path: P) -> Self {
+        self.config_path = config_path.as_ref().to_path_buf();
+        self
+    }
+    
+    /// Leave out the files matching the `exclude:` globs of a config when scanning the docs folder
+    pub fn with_exclude(mut self, exclude: Vec<String>) -> Self {
+        self.exclude = exclude;
+        self
+    }
+    
     /// Parse command-line arguments into ConfigOptions
-    pub fn parse_options(args: &[String]) -> Result<ConfigOptions, Box<dyn std::error::Error>> {
+    pub fn parse_options(args: &[String]) -> Result<ConfigOptions> {
         let mut options = ConfigOptions::default();
         let mut i = 0;
         
@@ -117,7 +155,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.title = Some(args[i].clone());
                     } else {
-                        return Err("--title requires a value".into());
+                        return Err(Error::usage("--title requires a value"));
                     }
                 }
                 "--description" => {
@@ -125,7 +163,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.description = Some(args[i].clone());
                     } else {
-                        return Err("--description requires a value".into());
+                        return Err(Error::usage("--description requires a value"));
                     }
                 }
                 "--section-order" => {
@@ -133,7 +171,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.section_order = Some(args[i].split(',').map(|s| s.trim().to_string()).collect());
                     } else {
-                        return Err("--section-order requires a comma-separated list".into());
+                        return Err(Error::usage("--section-order requires a comma-separated list"));
                     }
                 }
                 "--rename-section" => {
@@ -143,10 +181,10 @@ impl ConfigBuilder {
                         if parts.len() == 2 {
                             options.section_renames.insert(parts[0].to_string(), parts[1].to_string());
                         } else {
-                            return Err("--rename-section requires format 'old=new'".into());
+                            return Err(Error::usage("--rename-section requires format 'old=new'"));
                         }
                     } else {
-                        return Err("--rename-section requires a value".into());
+                        return Err(Error::usage("--rename-section requires a value"));
                     }
                 }
                 "--rename-page" => {
@@ -156,10 +194,10 @@ impl ConfigBuilder {
                         if parts.len() == 2 {
                             options.page_renames.insert(parts[0].to_string(), parts[1].to_string());
                         } else {
-                            return Err("--rename-page requires format 'section/file.md=New Title'".into());
+                            return Err(Error::usage("--rename-page requires format 'section/file.md=New Title'"));
                         }
                     } else {
-                        return Err("--rename-page requires a value".into());
+                        return Err(Error::usage("--rename-page requires a value"));
                     }
                 }
                 "--page-order" => {
@@ -170,10 +208,10 @@ impl ConfigBuilder {
                             let files: Vec<String> = parts[1].split(',').map(|s| s.trim().to_string()).collect();
                             options.page_orders.insert(parts[0].to_string(), files);
                         } else {
-                            return Err("--page-order requires format 'section=file1.md,file2.md'".into());
+                            return Err(Error::usage("--page-order requires format 'section=file1.md,file2.md'"));
                         }
                     } else {
-                        return Err("--page-order requires a value".into());
+                        return Err(Error::usage("--page-order requires a value"));
                     }
                 }
                 "--exclude-section" => {
@@ -181,7 +219,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.exclude_sections.push(args[i].clone());
                     } else {
-                        return Err("--exclude-section requires a value".into());
+                        return Err(Error::usage("--exclude-section requires a value"));
                     }
                 }
                 "--instagram" => {
@@ -189,7 +227,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.social.instagram = Some(args[i].clone());
                     } else {
-                        return Err("--instagram requires a value".into());
+                        return Err(Error::usage("--instagram requires a value"));
                     }
                 }
                 "--github" => {
@@ -197,7 +235,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.social.github = Some(args[i].clone());
                     } else {
-                        return Err("--github requires a value".into());
+                        return Err(Error::usage("--github requires a value"));
                     }
                 }
                 "--mastodon" => {
@@ -205,7 +243,7 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.social.mastodon = Some(args[i].clone());
                     } else {
-                        return Err("--mastodon requires a value".into());
+                        return Err(Error::usage("--mastodon requires a value"));
                     }
                 }
                 "--threads" => {
@@ -213,16 +251,30 @@ impl ConfigBuilder {
                     if i < args.len() {
                         options.social.threads = Some(args[i].clone());
                     } else {
-                        return Err("--threads requires a value".into());
+                        return Err(Error::usage("--threads requires a value"));
                     }
                 }
-                _ => {
-                    // Ignore unknown arguments for now
+                "--sync" => options.sync = true,
+                "--prune" => options.prune = true,
+                "--dry-run" => options.dry_run = true,
+                "--discard-comments" => options.discard_comments = true,
+                other => {
+                    return Err(Error::usage(format!("Unknown option '{}' for 'init-config'", other)));
                 }
             }
             i += 1;
         }
         
+        if options.prune && !options.sync {
+            return Err(Error::usage("--prune only applies to --sync"));
+        }
+        if options.discard_comments && !options.sync {
+            return Err(Error::usage("--discard-comments only applies to --sync"));
+        }
+        if options.sync && options.customizes() {
+            return Err(Error::usage("--sync keeps the titles and order of the existing config and only takes --prune, --dry-run and --discard-comments"));
+        }
+        
         Ok(options)
     }
     
@@ -232,7 +284,7 @@ impl ConfigBuilder {
         println!("============================");
         println!();
         println!("USAGE:");
-        println!("    cargo run init-config [OPTIONS]");
+        println!("    glowdoc init-config [--root <DIR>] [--config <FILE>] [--out <DIR>] [OPTIONS]");
         println!();
         println!("OPTIONS:");
         println!("    --title <TITLE>                    Set the site title");
@@ -257,6 +309,16 @@ impl ConfigBuilder {
         println!("    --mastodon <URL>                   Mastodon profile URL");
         println!("    --threads <USERNAME>               Threads username");
         println!();
+        println!("    --sync                             Update the existing config instead of replacing it: add");
+        println!("                                       new files, report deleted ones, keep titles, IDs and order");
+        println!("    --prune                            With --sync, remove the pages of deleted files");
+        println!("    --discard-comments                 With --sync, write a config that has comments, losing them");
+        println!("    --dry-run                          Print the changes to the config as a diff without writing");
+        println!();
+        println!("    --root <DIR>                       Docs folder to scan (default: docs)");
+        println!("    --config <FILE>                    Config file to write (default: <root>/config.yaml)");
+        println!("    --out <DIR>                        Output directory recorded in the config");
+        println!();
         println!("    --help                             Show this help message");
         println!();
         println!("EXAMPLES:");
@@ -271,93 +333,133 @@ impl ConfigBuilder {
         println!("        --rename-section setup=\"Quick Setup\" \\");
         println!("        --page-order setup=install.md,configure.md");
         println!();
+        println!("    # Add new pages to a hand-edited config, previewing the changes first");
+        println!("    cargo run init-config --sync --dry-run");
+        println!("    cargo run init-config --sync --prune");
+        println!();
         println!("    # Auto-detect with some customizations");
         println!("    cargo run init-config \\");
         println!("        --title \"API Docs\" \\");
         println!("        --exclude-section drafts");
     }
 
-    /// Auto-detect the documentation structure
-    pub fn detect_structure(&self) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    /// Auto-detect the documentation structure. Each folder of the docs folder is a section;
+    /// numeric prefixes such as `01-intro.md`, front matter `order`, and `order` in a folder's
+    /// `_section.yaml` or `index.md` set the position of pages and folders. Files ignored by
+    /// `.glowdocignore` or the `exclude:` globs are left out.
+    pub fn detect_structure(&self) -> crate::Result<DetectedConfig> {
+        let ignore = Ignore::load(&self.docs_path, &self.exclude)?;
         let mut sections = Vec::new();
+        let mut skipped = Vec::new();
         
-        // Read all directories in docs/
-        let entries = fs::read_dir(&self.docs_path)?;
-        let mut folders = Vec::new();
-        
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_dir() {
-                if let Some(folder_name) = path.file_name().and_then(|n| n.to_str()) {
-                    folders.push((folder_name.to_string(), path));
-                }
-            }
-        }
-        
-        // Sort folders alphabetically for consistent ordering
-        folders.sort_by(|a, b| a.0.cmp(&b.0));
-        
-        for (folder_name, folder_path) in folders {
-            let section = self.detect_section(&folder_name, &folder_path)?;
+        for (folder_name, folder_path) in self.read_folder(&self.docs_path, &ignore)?.1 {
+            let (order, section) = self.detect_section(&folder_name, &folder_path, &ignore, &mut skipped)?;
             if !section.items.is_empty() {
-                sections.push(section);
+                sections.push((order, section));
             }
         }
         
+        // Folders are read alphabetically, so unordered sections keep that order
+        sections.sort_by_key(|(order, _)| (order.is_none(), *order));
+        
         Ok(DetectedConfig {
             title: "Documentation".to_string(),
             description: "Generated documentation site".to_string(),
-            navigation: sections,
+            navigation: sections.into_iter().map(|(_, section)| section).collect(),
+            skipped,
         })
     }
     
-    fn detect_section(&self, folder_name: &str, folder_path: &Path) -> Result<DetectedSection, Box<dyn std::error::Error>> {
-        let items = self.detect_items_recursive(folder_path, folder_name, "")?;
-        
-        Ok(DetectedSection {
-            title: self.format_title(folder_name),
-            id: self.generate_id(folder_name),
-            original_folder: folder_name.to_string(),
-            items,
-        })
+    /// Print the files detection left out
+    fn print_skipped(detected: &DetectedConfig) {
+        for (path, reason) in &detected.skipped {
+            println!("⚠️  Skipped {}: {}", path.display(), reason);
+        }
     }
-
-    fn detect_items_recursive(&self, dir_path: &Path, base_folder: &str, relative_path: &str) -> Result<Vec<DetectedItem>, Box<dyn std::error::Error>> {
-        let mut items = Vec::new();
-        
-        // Read all entries in the directory
-        let entries = fs::read_dir(dir_path)?;
+    
+    /// The markdown files and the folders in `dir` that are not ignored, each sorted by name
+    #[allow(clippy::type_complexity)]
+    fn read_folder(&self, dir: &Path, ignore: &Ignore) -> crate::Result<(Vec<(String, PathBuf)>, Vec<(String, PathBuf)>)> {
         let mut files = Vec::new();
         let mut folders = Vec::new();
         
-        for entry in entries {
-            let entry = entry?;
-            let path = entry.path();
+        for entry in fs::read_dir(dir).map_err(error::io(dir))? {
+            let path = entry.map_err(error::io(dir))?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if ignore.is_ignored(&path) {
+                continue;
+            }
             
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "md") {
-                if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
-                    files.push((filename.to_string(), path));
-                }
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "md") {
+                files.push((name, path));
             } else if path.is_dir() {
-                if let Some(folder_name) = path.file_name().and_then(|n| n.to_str()) {
-                    folders.push((folder_name.to_string(), path));
-                }
+                folders.push((name, path));
             }
         }
         
-        // Sort files and folders alphabetically
         files.sort_by(|a, b| a.0.cmp(&b.0));
         folders.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok((files, folders))
+    }
+    
+    fn detect_section(&self, folder_name: &str, folder_path: &Path, ignore: &Ignore, skipped: &mut Vec<(PathBuf, String)>) -> crate::Result<(Option<i64>, DetectedSection)> {
+        let (number, name) = split_number_prefix(folder_name);
+        let meta = self.folder_meta(folder_path)?;
+        let items = self.detect_items_recursive(folder_path, folder_name, "", ignore, skipped)?;
+        
+        Ok((meta.order.or(number), DetectedSection {
+            title: meta.title.unwrap_or_else(|| self.format_title(name)),
+            id: meta.id.unwrap_or_else(|| self.generate_id(name)),
+            original_folder: folder_name.to_string(),
+            items,
+        }))
+    }
+    
+    /// Title, ID and position of a folder, from its `_section.yaml` or else the front matter of
+    /// its `index.md`. An `index.md` that cannot be read is skipped as a page, so it is ignored
+    /// here.
+    fn folder_meta(&self, folder_path: &Path) -> crate::Result<FolderMeta> {
+        let section_file = folder_path.join(SECTION_FILE);
+        if section_file.is_file() {
+            let content = fs::read_to_string(&section_file).map_err(error::io(&section_file))?;
+            return match serde_yaml::from_str::<Option<FolderMeta>>(&content) {
+                Ok(meta) => Ok(meta.unwrap_or_default()),
+                Err(e) => Err(Error::config(section_file, e.to_string())),
+            };
+        }
         
-        // Process files first
+        let index = folder_path.join(INDEX_FILE);
+        let front_matter = fs::read_to_string(&index).ok()
+            .and_then(|content| frontmatter::split(&content).ok().map(|(front_matter, _)| front_matter));
+        if let Some(front_matter) = front_matter {
+            return Ok(FolderMeta { title: front_matter.title, id: None, order: front_matter.order });
+        }
+        
+        Ok(FolderMeta::default())
+    }
+
+    fn detect_items_recursive(&self, dir_path: &Path, base_folder: &str, relative_path: &str, ignore: &Ignore, skipped: &mut Vec<(PathBuf, String)>) -> crate::Result<Vec<DetectedItem>> {
+        let (files, folders) = self.read_folder(dir_path, ignore)?;
+        
+        // The folder's index.md comes first, then pages and folders with an `order` in their
+        // front matter, `_section.yaml` or a numeric prefix, then the rest with files before
+        // folders
+        let mut items = Vec::new();
         for (filename, file_path) in files {
-            let item = self.detect_file_item(&filename, &file_path, base_folder, relative_path)?;
-            items.push(item);
+            let item = match self.detect_file_item(&filename, &file_path, base_folder, relative_path) {
+                Ok(item) => item,
+                Err(reason) => {
+                    skipped.push((file_path, reason));
+                    continue;
+                }
+            };
+            let rank = if filename == INDEX_FILE { 0 } else { 1 };
+            let order = item.front_matter.order.or(split_number_prefix(&filename).0);
+            items.push(((rank, order.is_none(), order, 0), item));
         }
         
-        // Process nested folders
         for (folder_name, folder_path) in folders {
             let nested_relative_path = if relative_path.is_empty() {
                 folder_name.clone()
@@ -365,30 +467,44 @@ impl ConfigBuilder {
                 format!("{}/{}", relative_path, folder_name)
             };
             
-            let nested_items = self.detect_items_recursive(&folder_path, base_folder, &nested_relative_path)?;
+            let nested_items = self.detect_items_recursive(&folder_path, base_folder, &nested_relative_path, ignore, skipped)?;
             
             if !nested_items.is_empty() {
                 // Create a folder item that contains nested items
+                let (number, name) = split_number_prefix(&folder_name);
+                let meta = self.folder_meta(&folder_path)?;
+                let order = meta.order.or(number);
                 let folder_item = DetectedItem {
-                    title: self.format_title(&folder_name),
-                    id: self.generate_id(&format!("{}_{}", relative_path.replace('/', "_"), folder_name)),
+                    title: meta.title.unwrap_or_else(|| self.format_title(name)),
+                    id: meta.id.unwrap_or_else(|| self.generate_id(&format!("{}_{}", id_path(relative_path), name))),
                     file: None,
                     original_filename: folder_name,
                     items: nested_items,
+                    front_matter: FrontMatter::default(),
                 };
-                items.push(folder_item);
+                items.push(((1, order.is_none(), order, 1), folder_item));
             }
         }
         
-        Ok(items)
+        items.sort_by_key(|(key, _)| *key);
+        Ok(items.into_iter().map(|(_, item)| item).collect())
     }
     
-    fn detect_file_item(&self, filename: &str, file_path: &Path, base_folder: &str, relative_path: &str) -> Result<DetectedItem, Box<dyn std::error::Error>> {
+    /// The page for a markdown file, or why it cannot be read
+    fn detect_file_item(&self, filename: &str, file_path: &Path, base_folder: &str, relative_path: &str) -> Result<DetectedItem, String> {
         let file_stem = filename.strip_suffix(".md").unwrap_or(filename);
+        let content = fs::read_to_string(file_path).map_err(|e| e.to_string())?;
+        let (front_matter, body) = frontmatter::split(&content)?;
         
-        // Try to extract title from the first H1 in the markdown file
-        let title = self.extract_title_from_markdown(file_path)
-            .unwrap_or_else(|| self.format_title(file_stem));
+        // Prefer the first H1 of an index.md, whose front matter describes its folder; for other
+        // pages prefer the front matter title, then the first H1
+        let title = if filename == INDEX_FILE {
+            self.extract_title_from_markdown(body).unwrap_or_else(|| "Overview".to_string())
+        } else {
+            front_matter.title.clone()
+                .or_else(|| self.extract_title_from_markdown(body))
+                .unwrap_or_else(|| self.format_title(split_number_prefix(file_stem).1))
+        };
         
         let full_relative_path = if relative_path.is_empty() {
             format!("{}/{}", base_folder, filename)
@@ -398,16 +514,16 @@ impl ConfigBuilder {
         
         Ok(DetectedItem {
             title,
-            id: self.generate_id(&format!("{}_{}", relative_path.replace('/', "_"), file_stem)),
+            id: self.generate_id(&format!("{}_{}", id_path(relative_path), split_number_prefix(file_stem).1)),
             file: Some(full_relative_path),
             original_filename: filename.to_string(),
             items: Vec::new(),
+            front_matter,
         })
     }
     
-    fn extract_title_from_markdown(&self, file_path: &Path) -> Option<String> {
-        let content = fs::read_to_string(file_path).ok()?;
-        let parser = Parser::new(&content);
+    fn extract_title_from_markdown(&self, content: &str) -> Option<String> {
+        let parser = Parser::new(content);
         
         let mut in_heading = false;
         let mut title_text = String::new();
@@ -436,8 +552,7 @@ impl ConfigBuilder {
     
     fn format_title(&self, input: &str) -> String {
         input
-            .replace('-', " ")
-            .replace('_', " ")
+            .replace(['-', '_'], " ")
             .split_whitespace()
             .map(|word| {
                 let mut chars = word.chars();
@@ -453,8 +568,7 @@ impl ConfigBuilder {
     fn generate_id(&self, input: &str) -> String {
         input
             .to_lowercase()
-            .replace(' ', "-")
-            .replace('_', "-")
+            .replace([' ', '_'], "-")
             .chars()
             .filter(|c| c.is_alphanumeric() || *c == '-')
             .collect::<String>()
@@ -463,9 +577,10 @@ impl ConfigBuilder {
     }
     
     /// Non-interactive configuration builder with options
-    pub fn build_config_with_options(&self, options: ConfigOptions) -> Result<Config, Box<dyn std::error::Error>> {
+    pub fn build_config_with_options(&self, options: ConfigOptions) -> Result<Config> {
         println!("🔍 Scanning docs folder...");
         let mut detected = self.detect_structure()?;
+        Self::print_skipped(&detected);
         
         // Apply site info
         if let Some(title) = options.title {
@@ -507,7 +622,7 @@ impl ConfigBuilder {
                 }
                 
                 // Then add any remaining items that weren't specified
-                reordered_items.extend(section.items.drain(..));
+                reordered_items.append(&mut section.items);
                 section.items = reordered_items;
             }
         }
@@ -527,49 +642,68 @@ impl ConfigBuilder {
             }
             
             // Then add any remaining sections that weren't specified
-            reordered_sections.extend(detected.navigation.drain(..));
+            reordered_sections.append(&mut detected.navigation);
             detected.navigation = reordered_sections;
         }
         
-        // Convert to final config format
-        let config = Config {
+        let config = self.to_config(detected, options.social);
+        
+        println!("✅ Configuration generated with {} sections", config.navigation.len());
+        Ok(config)
+    }
+
+    /// The config for a detected structure, in the theme of the config it replaces. Other
+    /// settings `init-config` does not manage start at their defaults; [`Self::save_config`]
+    /// carries them over.
+    fn to_config(&self, detected: DetectedConfig, social: SocialLinks) -> Config {
+        let theme = Config::load(&self.config_path).map_or_else(|_| crate::default_theme(), |existing| existing.theme);
+        Config {
             title: detected.title,
             description: detected.description,
             navigation: detected.navigation.into_iter().map(|section| {
-                ConfigSection {
+                NavigationSection {
                     title: section.title,
                     id: section.id,
                     items: section.items.into_iter().map(|item| {
                         self.convert_detected_item_to_config(item)
                     }).collect(),
+                    extra: Default::default(),
                 }
-            }).collect(),
-            social: options.social,
-        };
-        
-        println!("✅ Configuration generated with {} sections", config.navigation.len());
-        Ok(config)
+            }).collect::<Vec<_>>().into(),
+            theme,
+            social,
+            output_mode: OutputMode::default(),
+            root: None,
+            out: None,
+            preprocessors: Vec::new(),
+            exclude: Vec::new(),
+            extra: Default::default(),
+        }
     }
 
-    fn convert_detected_item_to_config(&self, item: DetectedItem) -> ConfigItem {
-        ConfigItem {
+    fn convert_detected_item_to_config(&self, item: DetectedItem) -> NavigationItem {
+        NavigationItem {
             title: item.title,
             id: item.id,
             file: item.file,
             items: item.items.into_iter().map(|nested_item| {
                 self.convert_detected_item_to_config(nested_item)
             }).collect(),
+            headers: None,
+            front_matter: FrontMatter::default(),
+            extra: Default::default(),
         }
     }
 
     /// Interactive configuration builder
-    pub fn build_config_interactive(&self) -> Result<Config, Box<dyn std::error::Error>> {
+    pub fn build_config_interactive(&self) -> Result<Config> {
         println!("🚀 GlowDoc Configuration Builder");
         println!("=================================\n");
         
         // Auto-detect structure
         println!("🔍 Scanning docs folder...");
         let mut detected = self.detect_structure()?;
+        Self::print_skipped(&detected);
         
         // Show detected structure
         self.print_detected_structure(&detected);
@@ -588,36 +722,36 @@ impl ConfigBuilder {
         let mut social = SocialLinks::default();
         
         print!("Instagram username: ");
-        io::stdout().flush()?;
+        io::stdout().flush().map_err(error::io("<stdout>"))?;
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         let instagram = input.trim();
         if !instagram.is_empty() {
             social.instagram = Some(instagram.to_string());
         }
         
         print!("GitHub username: ");
-        io::stdout().flush()?;
+        io::stdout().flush().map_err(error::io("<stdout>"))?;
         input.clear();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         let github = input.trim();
         if !github.is_empty() {
             social.github = Some(github.to_string());
         }
         
         print!("Mastodon profile URL: ");
-        io::stdout().flush()?;
+        io::stdout().flush().map_err(error::io("<stdout>"))?;
         input.clear();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         let mastodon = input.trim();
         if !mastodon.is_empty() {
             social.mastodon = Some(mastodon.to_string());
         }
         
         print!("Threads username: ");
-        io::stdout().flush()?;
+        io::stdout().flush().map_err(error::io("<stdout>"))?;
         input.clear();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         let threads = input.trim();
         if !threads.is_empty() {
             social.threads = Some(threads.to_string());
@@ -650,21 +784,7 @@ impl ConfigBuilder {
             _ => unreachable!()
         }
         
-        // Convert to final config format
-        let config = Config {
-            title: detected.title,
-            description: detected.description,
-            navigation: detected.navigation.into_iter().map(|section| {
-                ConfigSection {
-                    title: section.title,
-                    id: section.id,
-                    items: section.items.into_iter().map(|item| {
-                        self.convert_detected_item_to_config(item)
-                    }).collect(),
-                }
-            }).collect(),
-            social,
-        };
+        let config = self.to_config(detected, social);
         
         Ok(config)
     }
@@ -708,7 +828,7 @@ impl ConfigBuilder {
         }
     }
     
-    fn customize_sections(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn customize_sections(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         println!("\n🔧 Customizing Sections");
         println!("-----------------------");
         
@@ -741,7 +861,7 @@ impl ConfigBuilder {
         Ok(config)
     }
     
-    fn customize_pages(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn customize_pages(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         println!("\n📝 Customizing Pages");
         println!("--------------------");
         
@@ -780,7 +900,7 @@ impl ConfigBuilder {
         Ok(config)
     }
     
-    fn rename_page(&self, mut items: Vec<DetectedItem>) -> Result<Vec<DetectedItem>, Box<dyn std::error::Error>> {
+    fn rename_page(&self, mut items: Vec<DetectedItem>) -> Result<Vec<DetectedItem>> {
         if items.is_empty() {
             println!("No pages to rename.");
             return Ok(items);
@@ -805,7 +925,7 @@ impl ConfigBuilder {
         Ok(items)
     }
     
-    fn reorder_pages(&self, items: Vec<DetectedItem>) -> Result<Vec<DetectedItem>, Box<dyn std::error::Error>> {
+    fn reorder_pages(&self, items: Vec<DetectedItem>) -> Result<Vec<DetectedItem>> {
         if items.len() < 2 {
             println!("Need at least 2 pages to reorder.");
             return Ok(items);
@@ -818,10 +938,9 @@ impl ConfigBuilder {
         
         println!("\nEnter new order as space-separated numbers (e.g., '2 1 3 4'):");
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         
         let new_order: Result<Vec<usize>, _> = input
-            .trim()
             .split_whitespace()
             .map(|s| s.parse::<usize>().map(|n| n - 1))
             .collect();
@@ -840,7 +959,7 @@ impl ConfigBuilder {
         }
     }
     
-    fn rename_section(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn rename_section(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         if config.navigation.is_empty() {
             println!("No sections to rename.");
             return Ok(config);
@@ -862,7 +981,7 @@ impl ConfigBuilder {
         Ok(config)
     }
     
-    fn reorder_sections(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn reorder_sections(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         if config.navigation.len() < 2 {
             println!("Need at least 2 sections to reorder.");
             return Ok(config);
@@ -875,10 +994,9 @@ impl ConfigBuilder {
         
         println!("\nEnter new order as space-separated numbers (e.g., '2 1 3 4'):");
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         
         let new_order: Result<Vec<usize>, _> = input
-            .trim()
             .split_whitespace()
             .map(|s| s.parse::<usize>().map(|n| n - 1))
             .collect();
@@ -898,7 +1016,7 @@ impl ConfigBuilder {
         Ok(config)
     }
     
-    fn customize_section_pages(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn customize_section_pages(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         if config.navigation.is_empty() {
             println!("No sections available.");
             return Ok(config);
@@ -947,7 +1065,7 @@ impl ConfigBuilder {
         Ok(config)
     }
 
-    fn remove_section(&self, mut config: DetectedConfig) -> Result<DetectedConfig, Box<dyn std::error::Error>> {
+    fn remove_section(&self, mut config: DetectedConfig) -> Result<DetectedConfig> {
         if config.navigation.is_empty() {
             println!("No sections to remove.");
             return Ok(config);
@@ -965,12 +1083,12 @@ impl ConfigBuilder {
         Ok(config)
     }
     
-    fn prompt_with_default(&self, prompt: &str, default: &str) -> Result<String, Box<dyn std::error::Error>> {
+    fn prompt_with_default(&self, prompt: &str, default: &str) -> Result<String> {
         print!("{} [{}]: ", prompt, default);
-        io::stdout().flush()?;
+        io::stdout().flush().map_err(error::io("<stdout>"))?;
         
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
         
         let trimmed = input.trim();
         if trimmed.is_empty() {
@@ -980,13 +1098,13 @@ impl ConfigBuilder {
         }
     }
     
-    fn prompt_choice(&self, prompt: &str, min: usize, max: usize) -> Result<usize, Box<dyn std::error::Error>> {
+    fn prompt_choice(&self, prompt: &str, min: usize, max: usize) -> Result<usize> {
         loop {
             print!("{}: ", prompt);
-            io::stdout().flush()?;
+            io::stdout().flush().map_err(error::io("<stdout>"))?;
             
             let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
+            io::stdin().read_line(&mut input).map_err(error::io("<stdin>"))?;
             
             match input.trim().parse::<usize>() {
                 Ok(n) if n >= min && n <= max => return Ok(n),
@@ -995,13 +1113,695 @@ impl ConfigBuilder {
         }
     }
     
-    /// Save the configuration to docs/config.yaml
-    pub fn save_config(&self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-        let config_path = self.docs_path.join("config.yaml");
-        let yaml_content = serde_yaml::to_string(config)?;
-        fs::write(&config_path, yaml_content)?;
+    /// Save the configuration to the config path (docs/config.yaml by default). When a config
+    /// already exists there, the settings `init-config` does not generate are kept from it.
+    pub fn save_config(&self, config: &Config) -> Result<()> {
+        let config_path = &self.config_path;
+        let config = self.config_to_save(config);
+        
+        if let Some(parent) = config_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(error::io(parent))?;
+        }
+        let yaml_content = serde_yaml::to_string(&config).map_err(|e| Error::config(config_path, e.to_string()))?;
+        fs::write(config_path, yaml_content).map_err(error::io(config_path))?;
         
         println!("✅ Configuration saved to {}", config_path.display());
         Ok(())
     }
-}
\ No newline at end of file
+    
+    /// The changes [`Self::save_config`] would make to the config file, as a unified diff.
+    /// Empty when the file would stay the same.
+    pub fn diff_config(&self, config: &Config) -> Result<String> {
+        let current = fs::read_to_string(&self.config_path).unwrap_or_default();
+        let planned = serde_yaml::to_string(&self.config_to_save(config)).map_err(|e| Error::config(&self.config_path, e.to_string()))?;
+        Ok(unified_diff(&current, &planned, &self.config_path.display().to_string()))
+    }
+    
+    /// Whether the existing config file has comments, which [`Self::save_config`] does not keep
+    pub fn config_has_comments(&self) -> bool {
+        fs::read_to_string(&self.config_path).is_ok_and(|content| has_comments(&content))
+    }
+    
+    /// `config` with the settings it does not manage taken from the existing config file
+    fn config_to_save(&self, config: &Config) -> Config {
+        let mut config = config.clone();
+        if self.config_path.exists() {
+            match Config::load(&self.config_path) {
+                Ok(existing) => keep_unmanaged_settings(&mut config, existing),
+                Err(e) => println!("⚠️  Settings of the existing {} were not kept: {}", self.config_path.display(), e),
+            }
+        }
+        config
+    }
+    
+    /// Bring `existing` up to date with the docs folder without touching what it already
+    /// lists: titles, IDs and order stay as they are. Files missing from the navigation are
+    /// appended after the pages of the same folder, in a new folder or section when it has
+    /// none. Pages whose files were deleted are reported, or removed when `prune` is set.
+    pub fn sync_config(&self, existing: &Config, prune: bool) -> Result<(Config, Vec<SyncChange>)> {
+        if existing.navigation.auto.is_some() {
+            return Err(Error::usage("the navigation is derived from the docs folder at build time (`navigation: auto`), so there is nothing to sync"));
+        }
+        
+        let detected = self.detect_structure()?;
+        Self::print_skipped(&detected);
+        let mut config = existing.clone();
+        let mut changes = Vec::new();
+        
+        let is_deleted = |file: &str| !self.docs_path.join(file).is_file();
+        for section in config.navigation.iter_mut() {
+            prune_items(&mut section.items, prune, &is_deleted, &mut changes);
+        }
+        if prune {
+            // Sections left without pages go too, but empty ones from the config stay
+            let had_pages: HashSet<&str> = existing.navigation.iter()
+                .filter(|section| !section.items.is_empty())
+                .map(|section| section.id.as_str())
+                .collect();
+            config.navigation.retain(|section| !section.items.is_empty() || !had_pages.contains(section.id.as_str()));
+        }
+        
+        let end = config.navigation.len();
+        let added = self.add_detected_pages(&mut config.navigation, detected, end);
+        changes.extend(added.into_iter().map(SyncChange::Added));
+        
+        Ok((config, changes))
+    }
+    
+    /// Add the detected pages that `navigation` does not list yet, after the pages of the same
+    /// folder, or in the matching section or folder item, created as needed. New sections are
+    /// inserted at `insert_at`. Returns the files of the added pages.
+    pub(crate) fn add_detected_pages(&self, navigation: &mut Vec<NavigationSection>, detected: DetectedConfig, insert_at: usize) -> Vec<String> {
+        let mut listed = HashSet::new();
+        for section in navigation.iter() {
+            collect_files(&section.items, &mut listed);
+        }
+        
+        // Titles and IDs of the detected folders, by path relative to the docs folder
+        let mut folders = HashMap::new();
+        let mut new_files = Vec::new();
+        for section in &detected.navigation {
+            folders.insert(section.original_folder.clone(), (section.title.clone(), section.id.clone()));
+            collect_new_files(&section.items, &section.original_folder, &listed, &mut folders, &mut new_files);
+        }
+        
+        let mut insert_at = insert_at;
+        let mut added = Vec::new();
+        for (folder, item) in new_files {
+            let file = item.file.clone().unwrap_or_default();
+            let items = container_for(navigation, &folder, &folders, &mut insert_at);
+            let mut item = self.convert_detected_item_to_config(item);
+            item.id = unique_id(items.iter().map(|item| item.id.as_str()), &item.id);
+            items.push(item);
+            added.push(file);
+        }
+        added
+    }
+}
+
+/// Report the pages in `items` whose files were deleted, removing them (and folders left
+/// empty) when `prune` is set. Returns whether anything was removed.
+fn prune_items(items: &mut Vec<NavigationItem>, prune: bool, is_deleted: &impl Fn(&str) -> bool, changes: &mut Vec<SyncChange>) -> bool {
+    let mut removed = false;
+    items.retain_mut(|item| {
+        let emptied = prune_items(&mut item.items, prune, is_deleted, changes) && item.items.is_empty() && item.file.is_none();
+        let deleted = match item.file.as_deref() {
+            Some(file) if is_deleted(file) => {
+                changes.push(if prune { SyncChange::Removed(file.to_string()) } else { SyncChange::Missing(file.to_string()) });
+                prune
+            }
+            _ => false,
+        };
+        removed |= deleted || emptied;
+        !deleted && !emptied
+    });
+    removed
+}
+
+/// Page files of `items` and everything nested in them
+fn collect_files(items: &[NavigationItem], files: &mut HashSet<String>) {
+    for item in items {
+        files.extend(item.file.clone());
+        collect_files(&item.items, files);
+    }
+}
+
+/// Detected pages whose files are not in `listed`, in detection order, with the folder each
+/// is in. Records the title and ID of every detected folder in `folders`.
+fn collect_new_files(items: &[DetectedItem], folder: &str, listed: &HashSet<String>, folders: &mut HashMap<String, (String, String)>, new_files: &mut Vec<(String, DetectedItem)>) {
+    for item in items {
+        match &item.file {
+            Some(file) if !listed.contains(file) => new_files.push((folder.to_string(), item.clone())),
+            Some(_) => {}
+            None => {
+                let nested = format!("{}/{}", folder, item.original_filename);
+                folders.insert(nested.clone(), (item.title.clone(), item.id.clone()));
+                collect_new_files(&item.items, &nested, listed, folders, new_files);
+            }
+        }
+    }
+}
+
+/// Whether `items` lists a page whose file is directly in `folder`
+fn lists_folder(items: &[NavigationItem], folder: &str) -> bool {
+    items.iter().any(|item| item.file.as_deref().and_then(|file| file.rsplit_once('/')).is_some_and(|(dir, _)| dir == folder))
+}
+
+/// The items a new page in `folder` is appended to: wherever the config already lists pages of
+/// that folder, or else the section and folder items matching its path, created as needed.
+/// A new section is inserted at `insert_at`, which then moves past it.
+fn container_for<'a>(navigation: &'a mut Vec<NavigationSection>, folder: &str, folders: &HashMap<String, (String, String)>, insert_at: &mut usize) -> &'a mut Vec<NavigationItem> {
+    fn find(items: &[NavigationItem], folder: &str, path: &mut Vec<usize>) -> bool {
+        if lists_folder(items, folder) {
+            return true;
+        }
+        for (index, item) in items.iter().enumerate() {
+            path.push(index);
+            if find(&item.items, folder, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+    
+    let title_and_id = |path: &str| folders.get(path).cloned().unwrap_or_else(|| (path.to_string(), path.to_string()));
+    
+    for (section_index, section) in navigation.iter().enumerate() {
+        let mut path = Vec::new();
+        if find(&section.items, folder, &mut path) {
+            let mut items = &mut navigation[section_index].items;
+            for index in path {
+                items = &mut items[index].items;
+            }
+            return items;
+        }
+    }
+    
+    let mut parts = folder.split('/');
+    let mut current = parts.next().unwrap_or_default().to_string();
+    let (title, id) = title_and_id(&current);
+    let section_index = match navigation.iter().position(|section| section.id == id || lists_folder(&section.items, &current)) {
+        Some(index) => index,
+        None => {
+            let id = unique_id(navigation.iter().map(|section| section.id.as_str()), &id);
+            navigation.insert(*insert_at, NavigationSection { title, id, items: Vec::new(), extra: Default::default() });
+            *insert_at += 1;
+            *insert_at - 1
+        }
+    };
+    
+    let mut items = &mut navigation[section_index].items;
+    for part in parts {
+        current = format!("{}/{}", current, part);
+        let (title, id) = title_and_id(&current);
+        let index = match items.iter().position(|item| item.file.is_none() && (item.id == id || lists_folder(&item.items, &current))) {
+            Some(index) => index,
+            None => {
+                let id = unique_id(items.iter().map(|item| item.id.as_str()), &id);
+                items.push(NavigationItem {
+                    title,
+                    id,
+                    file: None,
+                    items: Vec::new(),
+                    headers: None,
+                    front_matter: FrontMatter::default(),
+                    extra: Default::default(),
+                });
+                items.len() - 1
+            }
+        };
+        items = &mut items[index].items;
+    }
+    items
+}
+
+/// The number and the rest of a file or folder name with a numeric prefix, such as `01-intro.md`
+fn split_number_prefix(name: &str) -> (Option<i64>, &str) {
+    let digits = name.len() - name.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = name[digits..].trim_start_matches(['-', '_', '.', ' ']);
+    if digits == 0 || rest.len() == name.len() - digits || rest.is_empty() || rest == "md" {
+        return (None, name);
+    }
+    (name[..digits].parse().ok(), rest)
+}
+
+/// `relative_path` for generating IDs: folder names without numeric prefixes, joined by `_`
+fn id_path(relative_path: &str) -> String {
+    relative_path.split('/').map(|folder| split_number_prefix(folder).1).collect::<Vec<_>>().join("_")
+}
+
+/// `id`, or `id-2`, `id-3`, ... when a sibling already uses it
+fn unique_id<'a>(siblings: impl Iterator<Item = &'a str> + Clone, id: &str) -> String {
+    if !siblings.clone().any(|sibling| sibling == id) {
+        return id.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", id, n))
+        .find(|candidate| !siblings.clone().any(|sibling| sibling == candidate))
+        .unwrap_or_default()
+}
+
+/// Whether `yaml` has a comment: a `#` that starts a line or follows a space outside a quoted
+/// string. `#`s in block scalars count too, which errs on the side of keeping the file.
+fn has_comments(yaml: &str) -> bool {
+    yaml.lines().any(|line| {
+        let mut quote = None;
+        let mut previous = ' ';
+        for c in line.chars() {
+            match (quote, c) {
+                (Some(open), c) if c == open => quote = None,
+                (Some(_), _) => {}
+                (None, '"' | '\'') if previous.is_whitespace() || matches!(previous, ':' | '[' | '{' | ',') => quote = Some(c),
+                (None, '#') if previous.is_whitespace() => return true,
+                _ => {}
+            }
+            previous = c;
+        }
+        false
+    })
+}
+
+/// Line diff of two versions of the file `name` in unified format, with three lines of
+/// context around each change. Empty when they are the same.
+fn unified_diff(old: &str, new: &str, name: &str) -> String {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    
+    // Length of the longest common subsequence of every pair of suffixes
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+        }
+    }
+    
+    // Every line tagged ' ', '-' or '+', with the old and new line numbers (0-based) it is at
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push((' ', old[i], i, j));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            lines.push(('-', old[i], i, j));
+            i += 1;
+        } else {
+            lines.push(('+', new[j], i, j));
+            j += 1;
+        }
+    }
+    
+    let changed: Vec<usize> = (0..lines.len()).filter(|&index| lines[index].0 != ' ').collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+    
+    let mut diff = format!("--- {}\n+++ {}\n", name, name);
+    let mut next = 0;
+    while next < changed.len() {
+        // Changes separated by less than twice the context share a hunk
+        let start = changed[next].saturating_sub(CONTEXT);
+        let mut last = changed[next];
+        while next + 1 < changed.len() && changed[next + 1] - last <= 2 * CONTEXT {
+            next += 1;
+            last = changed[next];
+        }
+        next += 1;
+        let hunk = &lines[start..(last + CONTEXT + 1).min(lines.len())];
+        
+        let old_count = hunk.iter().filter(|line| line.0 != '+').count();
+        let new_count = hunk.iter().filter(|line| line.0 != '-').count();
+        // An empty side is numbered by the line it follows
+        let old_start = hunk[0].2 + usize::from(old_count > 0);
+        let new_start = hunk[0].3 + usize::from(new_count > 0);
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count));
+        for (tag, text, _, _) in hunk {
+            diff.push_str(&format!("{}{}\n", tag, text));
+        }
+    }
+    diff
+}
+
+/// Copy what `init-config` does not generate from `existing` into `config`: the theme, output
+/// mode, paths, preprocessors and unknown keys, and the social links unless new ones were given
+fn keep_unmanaged_settings(config: &mut Config, mut existing: Config) {
+    // Unknown keys of sections and pages follow them by ID or by the files they list
+    for section in config.navigation.iter_mut() {
+        let mut files = HashSet::new();
+        collect_files(&section.items, &mut files);
+        let position = existing.navigation.iter().position(|old| old.id == section.id)
+            .or_else(|| existing.navigation.iter().position(|old| {
+                let mut old_files = HashSet::new();
+                collect_files(&old.items, &mut old_files);
+                !old_files.is_disjoint(&files)
+            }));
+        if let Some(position) = position {
+            let old = existing.navigation.remove(position);
+            section.extra = old.extra;
+            keep_item_keys(&mut section.items, old.items);
+        }
+    }
+
+    config.theme = existing.theme;
+    config.output_mode = existing.output_mode;
+    config.root = config.root.take().or(existing.root);
+    config.out = config.out.take().or(existing.out);
+    config.preprocessors = existing.preprocessors;
+    config.exclude = existing.exclude;
+    
+    let social = &config.social;
+    if social.instagram.is_none() && social.github.is_none() && social.mastodon.is_none() && social.threads.is_none() {
+        config.social = existing.social;
+    }
+    
+    let extra = std::mem::replace(&mut config.extra, existing.extra);
+    config.extra.extend(extra);
+}
+
+/// Copy the unknown keys of `existing` items to the items of `items` with the same file, or
+/// for folders the same ID, and likewise for their nested items
+fn keep_item_keys(items: &mut [NavigationItem], mut existing: Vec<NavigationItem>) {
+    for item in items {
+        let position = existing.iter().position(|old| match &item.file {
+            Some(file) => old.file.as_ref() == Some(file),
+            None => old.file.is_none() && old.id == item.id,
+        });
+        if let Some(position) = position {
+            let old = existing.remove(position);
+            item.extra = old.extra;
+            keep_item_keys(&mut item.items, old.items);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A docs folder with one section and page, and `config` as its config.yaml
+    fn docs_folder(name: &str, config: &str) -> PathBuf {
+        let docs = std::env::temp_dir().join(format!("glowdoc-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&docs);
+        fs::create_dir_all(docs.join("guide")).unwrap();
+        fs::write(docs.join("guide/intro.md"), "# Intro\n\nHello.\n").unwrap();
+        fs::write(docs.join("config.yaml"), config).unwrap();
+        docs
+    }
+    
+    const EXISTING_CONFIG: &str = "\
+title: Old Title
+description: Old description
+theme: purple
+output_mode: multi-page
+social:
+  github: octocat
+preprocessors:
+  - name: stamp
+    command: python3 stamp.py
+    renderers: [single-file]
+analytics:
+  id: UA-1234
+navigation:
+  - title: Old
+    id: old
+    items:
+      - title: Gone
+        id: gone
+        file: old/gone.md
+";
+    
+    #[test]
+    fn unknown_keys_round_trip() {
+        let yaml = "\
+title: T
+description: D
+analytics:
+  id: UA-1234
+navigation:
+  - title: Guide
+    id: guide
+    collapsed: true
+    items:
+      - title: Intro
+        id: intro
+        file: guide/intro.md
+        badge: new
+";
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let reparsed: serde_yaml::Value = serde_yaml::from_str(&serde_yaml::to_string(&config).unwrap()).unwrap();
+        
+        assert_eq!(reparsed["analytics"]["id"], "UA-1234");
+        assert_eq!(reparsed["navigation"][0]["collapsed"], true);
+        assert_eq!(reparsed["navigation"][0]["items"][0]["badge"], "new");
+    }
+    
+    #[test]
+    fn init_config_keeps_unmanaged_settings() {
+        let docs = docs_folder("keep", EXISTING_CONFIG);
+        let builder = ConfigBuilder::new(&docs);
+        let options = ConfigOptions { title: Some("New Title".to_string()), ..Default::default() };
+        builder.save_config(&builder.build_config_with_options(options).unwrap()).unwrap();
+        
+        let saved = Config::load(docs.join("config.yaml")).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        // Managed settings are regenerated
+        assert_eq!(saved.title, "New Title");
+        assert_eq!(saved.navigation.len(), 1);
+        assert_eq!(saved.navigation[0].items[0].file.as_deref(), Some("guide/intro.md"));
+        
+        // Everything else is kept
+        assert_eq!(saved.theme, "purple");
+        assert_eq!(saved.output_mode, OutputMode::MultiPage);
+        assert_eq!(saved.social.github.as_deref(), Some("octocat"));
+        assert_eq!(saved.preprocessors.len(), 1);
+        assert_eq!(saved.preprocessors[0].renderers, vec![OutputMode::SingleFile]);
+        assert_eq!(saved.extra["analytics"]["id"], "UA-1234");
+    }
+    
+    #[test]
+    fn init_config_keeps_the_theme_and_unknown_keys_of_sections_and_pages() {
+        let docs = docs_folder("nested-keys", "\
+title: T
+description: D
+theme: vibrant
+navigation:
+  - title: My Guide
+    id: handbook
+    collapsed: true
+    items:
+      - title: Welcome
+        id: welcome
+        file: guide/intro.md
+        badge: new
+      - id: gone
+        file: guide/gone.md
+        badge: old
+");
+        let builder = ConfigBuilder::new(&docs);
+        let config = builder.build_config_with_options(ConfigOptions::default()).unwrap();
+        assert_eq!(config.theme, "vibrant");
+        builder.save_config(&config).unwrap();
+        
+        let content = fs::read_to_string(docs.join("config.yaml")).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        let saved: serde_yaml::Value = serde_yaml::from_str(&content).unwrap();
+        
+        assert_eq!(saved["theme"], "vibrant");
+        assert_eq!(saved["navigation"][0]["id"], "guide");
+        assert_eq!(saved["navigation"][0]["collapsed"], true);
+        assert_eq!(saved["navigation"][0]["items"][0]["file"], "guide/intro.md");
+        assert_eq!(saved["navigation"][0]["items"][0]["badge"], "new");
+        assert!(!content.contains("badge: old"));
+    }
+    
+    #[test]
+    fn pages_without_a_title_are_written_without_one() {
+        let item = NavigationItem {
+            title: String::new(),
+            id: "intro".to_string(),
+            file: Some("guide/intro.md".to_string()),
+            items: Vec::new(),
+            headers: None,
+            front_matter: FrontMatter::default(),
+            extra: Default::default(),
+        };
+        let yaml = serde_yaml::to_string(&item).unwrap();
+        assert!(!yaml.contains("title"), "{}", yaml);
+        assert_eq!(serde_yaml::from_str::<NavigationItem>(&yaml).unwrap().title, "");
+    }
+    
+    #[test]
+    fn init_config_replaces_social_links_given_as_options() {
+        let docs = docs_folder("social", EXISTING_CONFIG);
+        let builder = ConfigBuilder::new(&docs);
+        let mut options = ConfigOptions::default();
+        options.social.mastodon = Some("@me@example.social".to_string());
+        builder.save_config(&builder.build_config_with_options(options).unwrap()).unwrap();
+        
+        let saved = Config::load(docs.join("config.yaml")).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        assert_eq!(saved.social.mastodon.as_deref(), Some("@me@example.social"));
+        assert_eq!(saved.social.github, None);
+        assert_eq!(saved.theme, "purple");
+    }
+    
+    #[test]
+    fn init_config_without_existing_config_uses_defaults() {
+        let docs = docs_folder("fresh", "");
+        fs::remove_file(docs.join("config.yaml")).unwrap();
+        let builder = ConfigBuilder::new(&docs);
+        builder.save_config(&builder.build_config_with_options(ConfigOptions::default()).unwrap()).unwrap();
+        
+        let saved = Config::load(docs.join("config.yaml")).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        assert_eq!(saved.theme, "default");
+        assert_eq!(saved.output_mode, OutputMode::SingleFile);
+        assert!(saved.extra.is_empty());
+    }
+    
+    #[test]
+    fn sync_appends_new_files_and_keeps_hand_edits() {
+        let docs = docs_folder("sync", "\
+title: T
+description: D
+navigation:
+  - title: My Guide
+    id: my-guide
+    items:
+      - title: Welcome
+        id: welcome
+        file: guide/intro.md
+      - title: Gone
+        id: gone
+        file: guide/gone.md
+");
+        fs::write(docs.join("guide/next.md"), "# Next\n").unwrap();
+        fs::create_dir_all(docs.join("guide/more")).unwrap();
+        fs::write(docs.join("guide/more/deep.md"), "# Deep\n").unwrap();
+        let builder = ConfigBuilder::new(&docs);
+        let existing = Config::load(docs.join("config.yaml")).unwrap();
+        
+        let (synced, changes) = builder.sync_config(&existing, false).unwrap();
+        let (pruned, _) = builder.sync_config(&existing, true).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        assert_eq!(changes, vec![
+            SyncChange::Missing("guide/gone.md".to_string()),
+            SyncChange::Added("guide/next.md".to_string()),
+            SyncChange::Added("guide/more/deep.md".to_string()),
+        ]);
+        let section = &synced.navigation[0];
+        assert_eq!(synced.navigation.len(), 1);
+        assert_eq!((section.title.as_str(), section.id.as_str()), ("My Guide", "my-guide"));
+        let titles: Vec<&str> = section.items.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(titles, ["Welcome", "Gone", "Next", "More"]);
+        assert_eq!(section.items[3].items[0].file.as_deref(), Some("guide/more/deep.md"));
+        
+        let titles: Vec<&str> = pruned.navigation[0].items.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(titles, ["Welcome", "Next", "More"]);
+    }
+    
+    #[test]
+    fn comments_are_found_outside_quoted_strings() {
+        assert!(has_comments("# Site settings\ntitle: T\n"));
+        assert!(has_comments("title: T  # shown in the header\n"));
+        assert!(!has_comments("title: \"C# and F# # notes\"\nurl: https://example.com/#top\n"));
+        assert!(!has_comments("description: 'Issue #12'\n"));
+    }
+    
+    #[test]
+    fn detection_honors_number_prefixes_and_folder_metadata() {
+        let docs = docs_folder("detect", "title: T\ndescription: D\n");
+        fs::create_dir_all(docs.join("01-start/02-more")).unwrap();
+        fs::write(docs.join("01-start/02-install.md"), "# Install\n").unwrap();
+        fs::write(docs.join("01-start/10-usage.md"), "# Usage\n").unwrap();
+        fs::write(docs.join("01-start/index.md"), "---\ntitle: Getting Started\n---\n# Overview\n").unwrap();
+        fs::write(docs.join("01-start/02-more/tips.md"), "---\ntitle: Tips\n---\n").unwrap();
+        fs::write(docs.join("guide/_section.yaml"), "title: User Guide\nid: manual\norder: 0\n").unwrap();
+        let detected = ConfigBuilder::new(&docs).detect_structure().unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        let sections: Vec<(&str, &str)> = detected.navigation.iter().map(|section| (section.title.as_str(), section.id.as_str())).collect();
+        assert_eq!(sections, [("User Guide", "manual"), ("Getting Started", "start")]);
+        let items: Vec<(&str, &str)> = detected.navigation[1].items.iter().map(|item| (item.title.as_str(), item.id.as_str())).collect();
+        assert_eq!(items, [("Overview", "index"), ("Install", "install"), ("More", "more"), ("Usage", "usage")]);
+        assert_eq!(detected.navigation[1].items[2].items[0].id, "more-tips");
+    }
+    
+    #[test]
+    fn detection_skips_unreadable_pages() {
+        let docs = docs_folder("detect-skip", "title: T\ndescription: D\n");
+        fs::write(docs.join("guide/broken.md"), "---\nlayout: narrow\n---\n# Broken\n").unwrap();
+        fs::write(docs.join("guide/binary.md"), [0xff, 0xfe, 0x00]).unwrap();
+        fs::write(docs.join("guide/index.md"), "---\ntitle: [unclosed\n---\n").unwrap();
+        let detected = ConfigBuilder::new(&docs).detect_structure().unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+        
+        let files: Vec<Option<&str>> = detected.navigation[0].items.iter().map(|item| item.file.as_deref()).collect();
+        assert_eq!(files, [Some("guide/intro.md")]);
+        assert_eq!(detected.navigation[0].title, "Guide");
+        let skipped: Vec<&Path> = detected.skipped.iter().map(|(path, _)| path.strip_prefix(&docs).unwrap()).collect();
+        assert_eq!(skipped, [Path::new("guide/binary.md"), Path::new("guide/broken.md"), Path::new("guide/index.md")]);
+        assert!(detected.skipped[1].1.starts_with("unknown layout 'narrow'"), "{}", detected.skipped[1].1);
+    }
+    
+    #[test]
+    fn derived_pages_fill_in_explicit_entries() {
+        let docs = docs_folder("derive", "title: T\ndescription: D\n");
+        fs::write(docs.join("guide/next.md"), "# Next\n").unwrap();
+        fs::create_dir_all(docs.join("api")).unwrap();
+        fs::write(docs.join("api/calls.md"), "# Calls\n").unwrap();
+        let mut config: Config = serde_yaml::from_str("\
+title: T
+description: D
+navigation:
+  - title: Guide
+    id: guide
+    items:
+      - title: Next Steps
+        id: next
+        file: guide/next.md
+  - auto
+").unwrap();
+        assert_eq!(serde_yaml::to_value(&config).unwrap()["navigation"][1], "auto");
+        
+        let builder = ConfigBuilder::new(&docs);
+        let position = config.navigation.auto.take().unwrap();
+        let added = builder.add_detected_pages(&mut config.navigation, builder.detect_structure().unwrap(), position);
+        fs::remove_dir_all(&docs).unwrap();
+        
+        assert_eq!(added, ["api/calls.md", "guide/intro.md"]);
+        let titles: Vec<&str> = config.navigation[0].items.iter().map(|item| item.title.as_str()).collect();
+        assert_eq!(titles, ["Next Steps", "Intro"]);
+        assert_eq!(config.navigation[1].id, "api");
+    }
+    
+    #[test]
+    fn unified_diff_groups_changes_into_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        assert_eq!(unified_diff(old, old, "c.yaml"), "");
+        assert_eq!(unified_diff(old, new, "c.yaml"), "\
+--- c.yaml
++++ c.yaml
+@@ -1,5 +1,5 @@
+ a
+-b
++B
+ c
+ d
+ e
+@@ -8,3 +8,4 @@
+ h
+ i
+ j
++k
+");
+    }
+}
@@ -0,0 +1,118 @@
+/// Callout blocks rendered by `admonition`. Every kind takes its color from the theme:
+/// notes `--primary`, the others `--success`, `--important`, `--warning` and `--danger`, and
+/// deprecation notices the muted text.
+pub fn generate_admonition_css() -> String {
+    r#"        .admonition {
+            --admonition-color: var(--primary);
+            margin: 1.5rem 0;
+            padding: 0.75rem 1rem;
+            border: 1px solid hsl(var(--admonition-color) / 0.3);
+            border-left: 4px solid hsl(var(--admonition-color));
+            border-radius: var(--radius);
+            background-color: hsl(var(--admonition-color) / 0.06);
+            color: hsl(var(--foreground));
+        }
+
+        .admonition-tip {
+            --admonition-color: var(--success);
+        }
+
+        .admonition-important {
+            --admonition-color: var(--important);
+        }
+
+        .admonition-warning {
+            --admonition-color: var(--warning);
+        }
+
+        .admonition-caution,
+        .admonition-danger {
+            --admonition-color: var(--danger);
+        }
+
+        .admonition-deprecated {
+            --admonition-color: var(--muted-foreground);
+        }
+
+        .admonition-title {
+            display: flex;
+            align-items: center;
+            gap: 0.5rem;
+            margin: 0 0 0.5rem 0;
+            font-weight: 600;
+            color: hsl(var(--admonition-color));
+        }
+
+        .admonition-title::before {
+            content: "ℹ";
+            display: inline-flex;
+            align-items: center;
+            justify-content: center;
+            width: 1.25rem;
+            height: 1.25rem;
+            flex-shrink: 0;
+            border-radius: 50%;
+            background-color: hsl(var(--admonition-color));
+            color: hsl(var(--background));
+            font-size: 0.75rem;
+            font-weight: 700;
+        }
+
+        .admonition-tip > .admonition-title::before {
+            content: "✓";
+        }
+
+        .admonition-important > .admonition-title::before,
+        .admonition-warning > .admonition-title::before,
+        .admonition-caution > .admonition-title::before,
+        .admonition-danger > .admonition-title::before {
+            content: "!";
+        }
+
+        .admonition-deprecated > .admonition-title::before {
+            content: "×";
+        }
+
+        .admonition > :last-child {
+            margin-bottom: 0;
+        }
+
+        .admonition-deprecated > :not(.admonition-title) {
+            color: hsl(var(--muted-foreground));
+        }
+
+        details.admonition > .admonition-title {
+            cursor: pointer;
+            list-style: none;
+            user-select: none;
+        }
+
+        details.admonition > .admonition-title::-webkit-details-marker {
+            display: none;
+        }
+
+        details.admonition > .admonition-title::after {
+            content: "";
+            width: 0.5rem;
+            height: 0.5rem;
+            margin-left: auto;
+            border-right: 2px solid currentColor;
+            border-bottom: 2px solid currentColor;
+            transform: rotate(-45deg);
+            transition: transform 0.2s ease;
+        }
+
+        details.admonition[open] > .admonition-title::after {
+            transform: rotate(45deg);
+        }
+
+        details.admonition:not([open]) > .admonition-title {
+            margin-bottom: 0;
+        }
+
+        details.admonition > .admonition-title:focus-visible {
+            outline: 2px solid hsl(var(--ring));
+            outline-offset: 2px;
+            border-radius: calc(var(--radius) - 4px);
+        }"#.to_string()
+}
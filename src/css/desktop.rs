@@ -89,7 +89,7 @@ pub fn generate_desktop_css() -> String {
             background-color: hsl(var(--accent));
         }
 
-        .theme-toggle {
+        .theme-toggle, .search-toggle {
             background: none;
             border: 1px solid hsl(var(--border));
             border-radius: var(--radius);
@@ -99,7 +99,7 @@ pub fn generate_desktop_css() -> String {
             transition: all 0.2s;
         }
 
-        .theme-toggle:hover {
+        .theme-toggle:hover, .search-toggle:hover {
             background-color: hsl(var(--accent));
         }
 
@@ -276,6 +276,108 @@ pub fn generate_desktop_css() -> String {
             color: hsl(var(--muted-foreground));
         }
 
+        .search-trigger {
+            display: flex;
+            align-items: center;
+            justify-content: space-between;
+            width: 100%;
+            padding: 0.75rem 2.5rem 0.75rem 1rem;
+            border: 1px solid hsl(var(--border));
+            border-radius: var(--radius);
+            background-color: hsl(var(--background));
+            color: hsl(var(--muted-foreground));
+            font-size: 0.875rem;
+            cursor: pointer;
+            transition: all 0.2s;
+        }
+
+        .search-trigger:hover, .search-trigger:focus-visible {
+            outline: none;
+            border-color: hsl(var(--primary));
+        }
+
+        .search-palette kbd, .search-trigger kbd {
+            font-family: inherit;
+            font-size: 0.75rem;
+            padding: 0.05rem 0.35rem;
+            border: 1px solid hsl(var(--border));
+            border-radius: calc(var(--radius) - 4px);
+            background-color: hsl(var(--muted));
+            color: hsl(var(--muted-foreground));
+        }
+
+        body.search-open {
+            overflow: hidden;
+        }
+
+        .search-palette {
+            position: fixed;
+            inset: 0;
+            z-index: 100;
+            display: flex;
+            justify-content: center;
+            align-items: flex-start;
+            padding: 10vh 1rem 1rem;
+        }
+
+        .search-palette[hidden] {
+            display: none;
+        }
+
+        .search-palette-backdrop {
+            position: absolute;
+            inset: 0;
+            background-color: hsl(var(--foreground) / 0.4);
+        }
+
+        .search-palette-dialog {
+            position: relative;
+            display: flex;
+            flex-direction: column;
+            width: 100%;
+            max-width: 640px;
+            max-height: 75vh;
+            background-color: hsl(var(--popover));
+            color: hsl(var(--popover-foreground));
+            border: 1px solid hsl(var(--border));
+            border-radius: var(--radius);
+            box-shadow: 0 20px 50px hsl(var(--foreground) / 0.25);
+            overflow: hidden;
+        }
+
+        .search-palette .search-container {
+            margin: 0;
+            border-bottom: 1px solid hsl(var(--border));
+        }
+
+        .search-palette .search-input {
+            border: none;
+            border-radius: 0;
+            padding: 1rem 2.75rem 1rem 1rem;
+            font-size: 1rem;
+            background-color: transparent;
+        }
+
+        .search-palette .search-input:focus {
+            box-shadow: none;
+        }
+
+        .search-palette .search-results {
+            flex: 1;
+            overflow-y: auto;
+            margin: 0;
+            padding: 0.75rem;
+        }
+
+        .search-palette-footer {
+            display: flex;
+            gap: 1rem;
+            padding: 0.5rem 1rem;
+            border-top: 1px solid hsl(var(--border));
+            color: hsl(var(--muted-foreground));
+            font-size: 0.75rem;
+        }
+
         .search-icon {
             position: absolute;
             right: 0.75rem;
@@ -289,17 +391,6 @@ pub fn generate_desktop_css() -> String {
             margin-bottom: 1.5rem;
         }
 
-        .search-results-header {
-            font-weight: 600;
-            color: hsl(var(--foreground));
-            margin-bottom: 0.75rem;
-            font-size: 0.875rem;
-            text-transform: uppercase;
-            letter-spacing: 0.025em;
-            border-bottom: 1px solid hsl(var(--border));
-            padding-bottom: 0.5rem;
-        }
-
         .search-result {
             padding: 0.75rem;
             margin-bottom: 0.5rem;
@@ -310,7 +401,7 @@ pub fn generate_desktop_css() -> String {
             background-color: hsl(var(--card));
         }
 
-        .search-result:hover {
+        .search-result:hover, .search-result.selected {
             background-color: hsl(var(--accent));
             border-color: hsl(var(--primary));
         }
@@ -511,6 +602,15 @@ pub fn generate_desktop_css() -> String {
             padding-right: 310px;
         }
 
+        /* Pages with `layout: wide` in their front matter use the full width without a table of contents */
+        .content-area:has(.content-section.active.page-layout-wide) {
+            padding-right: 0;
+        }
+
+        .content-wrapper:has(.content-section.active.page-layout-wide) .table-of-contents {
+            display: none;
+        }
+
         .table-of-contents {
             width: 250px;
             flex-shrink: 0;
@@ -29,6 +29,22 @@ pub fn generate_mobile_css() -> String {
                 transform: translateX(0);
             }
 
+            .search-palette {
+                padding: 0;
+            }
+
+            .search-palette-dialog {
+                max-width: none;
+                max-height: 100vh;
+                height: 100%;
+                border: none;
+                border-radius: 0;
+            }
+
+            .search-palette-footer {
+                display: none;
+            }
+
             // .main-content {
             //     padding: 1rem;
             // }
@@ -1,8 +1,15 @@
+pub mod admonition;
 pub mod desktop;
 pub mod mobile;
+pub mod syntax;
 
+use admonition::generate_admonition_css;
 use desktop::generate_desktop_css;
 use mobile::generate_mobile_css;
+use syntax::generate_syntax_css;
+
+/// Themes `generate_css` knows; any other name gets the default colors
+pub const THEMES: [&str; 3] = ["default", "purple", "vibrant"];
 
 pub fn generate_css(theme: &str) -> String {
     let theme_vars = match theme {
@@ -27,6 +34,10 @@ pub fn generate_css(theme: &str) -> String {
             --input: 270 20% 88%;
             --ring: 270 91% 65%;
             --link: 50 100% 40%;
+            --success: 150 60% 32%;
+            --important: 320 70% 48%;
+            --warning: 35 92% 40%;
+            --danger: 0 75% 50%;
             --radius: 0.5rem;
         }
 
@@ -51,6 +62,10 @@ pub fn generate_css(theme: &str) -> String {
             --input: 270 15% 20%;
             --ring: 270 91% 75%;
             --link: 50 100% 60%;
+            --success: 150 50% 58%;
+            --important: 320 80% 75%;
+            --warning: 40 95% 60%;
+            --danger: 0 85% 68%;
         }"#,
         "vibrant" => r#"        :root {
             --background: 45 100% 92%;
@@ -73,6 +88,10 @@ pub fn generate_css(theme: &str) -> String {
             --input: 40 50% 80%;
             --ring: 200 100% 50%;
             --link: 320 80% 50%;
+            --success: 120 55% 30%;
+            --important: 280 65% 52%;
+            --warning: 25 95% 42%;
+            --danger: 350 75% 48%;
             --radius: 0.5rem;
         }
 
@@ -97,6 +116,10 @@ pub fn generate_css(theme: &str) -> String {
             --input: 210 20% 35%;
             --ring: 200 90% 65%;
             --link: 320 80% 60%;
+            --success: 120 45% 62%;
+            --important: 280 80% 76%;
+            --warning: 35 95% 62%;
+            --danger: 350 85% 70%;
         }"#,
         _ => r#"        :root {
             --background: 0 0% 100%;
@@ -119,6 +142,10 @@ pub fn generate_css(theme: &str) -> String {
             --input: 214.3 31.8% 91.4%;
             --ring: 222.2 84% 4.9%;
             --link: 120 100% 35%;
+            --success: 142 64% 32%;
+            --important: 262 60% 50%;
+            --warning: 35 92% 40%;
+            --danger: 0 72% 48%;
             --radius: 0.5rem;
         }
 
@@ -143,10 +170,16 @@ pub fn generate_css(theme: &str) -> String {
             --input: 217.2 32.6% 17.5%;
             --ring: 212.7 26.8% 83.9%;
             --link: 120 100% 50%;
+            --success: 142 55% 55%;
+            --important: 262 85% 75%;
+            --warning: 40 95% 58%;
+            --danger: 0 85% 65%;
         }"#,
     };
 
     format!("{}
 {}
-{}", theme_vars, generate_desktop_css(), generate_mobile_css())
+{}
+{}
+{}", theme_vars, generate_syntax_css(), generate_desktop_css(), generate_admonition_css(), generate_mobile_css())
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+/// Colors for the code highlighted at build time by `highlight`. Keywords, tags and comments
+/// follow the active theme; the remaining tokens use a light and a dark palette.
+pub fn generate_syntax_css() -> String {
+    r#"        :root {
+            --syntax-string: 142 64% 28%;
+            --syntax-number: 24 90% 40%;
+            --syntax-type: 199 89% 32%;
+            --syntax-function: 262 60% 48%;
+            --syntax-property: 330 65% 40%;
+        }
+
+        [data-theme="dark"] {
+            --syntax-string: 142 55% 65%;
+            --syntax-number: 30 95% 65%;
+            --syntax-type: 190 80% 65%;
+            --syntax-function: 262 85% 78%;
+            --syntax-property: 330 80% 75%;
+        }
+
+        .tok-keyword,
+        .tok-tag {
+            color: hsl(var(--primary));
+            font-weight: 600;
+        }
+
+        .tok-comment {
+            color: hsl(var(--muted-foreground));
+            font-style: italic;
+        }
+
+        .tok-string {
+            color: hsl(var(--syntax-string));
+        }
+
+        .tok-number,
+        .tok-literal {
+            color: hsl(var(--syntax-number));
+        }
+
+        .tok-type {
+            color: hsl(var(--syntax-type));
+        }
+
+        .tok-function {
+            color: hsl(var(--syntax-function));
+        }
+
+        .tok-property {
+            color: hsl(var(--syntax-property));
+        }"#.to_string()
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::{BrokenLink, Diagnostic};
+
+/// Result of loading, rendering and checking a site
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Why a site could not be loaded, rendered or checked, with the file the problem is in
+#[derive(Debug)]
+pub enum Error {
+    /// The config file cannot be read or parsed, or its validation found errors (in a strict
+    /// build, any problem)
+    Config { path: PathBuf, problems: Vec<Diagnostic> },
+    /// Reading or writing a file failed
+    Io { path: PathBuf, source: io::Error },
+    /// A page or entry.md cannot be read or parsed: the file is missing, has invalid front
+    /// matter or an unknown layout, or one of its includes fails
+    Markdown { path: PathBuf, message: String },
+    /// Files referenced from the pages, such as images, do not exist
+    Assets { missing: Vec<String> },
+    /// A preprocessor failed or returned something other than pages of the book
+    Plugin { name: String, command: String, message: String },
+    /// Links to pages that a strict build could not resolve, e.g.
+    /// `guide/setup.md: link 'old.md' was not rewritten: ...`
+    Links { broken: Vec<String> },
+    /// The search index could not be serialized to JSON
+    SearchIndex { message: String },
+    /// The HTTP or WebSocket server of `serve` and `watch` could not listen on `address`
+    Server { address: String, source: io::Error },
+    /// Command-line options that cannot be used, such as an unknown `init-config` option
+    Usage { message: String },
+    /// Problems found by [`crate::GlowDocBuilder::check`], by kind: config diagnostics, files
+    /// that cannot be read or parsed, failed preprocessors and broken links. `warnings` are
+    /// reported too but do not fail the check.
+    Check {
+        config: Vec<Diagnostic>,
+        pages: Vec<Error>,
+        plugins: Vec<Error>,
+        links: Vec<BrokenLink>,
+        warnings: Vec<String>,
+    },
+}
+
+impl Error {
+    /// A single config problem without a position, such as a config that cannot be read
+    pub fn config(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Error::Config {
+            path: path.into(),
+            problems: vec![Diagnostic { severity: crate::Severity::Error, position: None, message: message.into() }],
+        }
+    }
+
+    /// Command-line options that cannot be used
+    pub fn usage(message: impl Into<String>) -> Self {
+        Error::Usage { message: message.into() }
+    }
+}
+
+/// Turn an I/O error on `path` into an [`Error::Io`], for use with `map_err`
+pub(crate) fn io(path: impl AsRef<Path>) -> impl FnOnce(io::Error) -> Error {
+    let path = path.as_ref().to_path_buf();
+    move |source| Error::Io { path, source }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Config { path, problems } => {
+                let path = path.display().to_string();
+                match problems.as_slice() {
+                    [problem] => write!(f, "{}", problem.display(&path)),
+                    problems => {
+                        write!(f, "{} problems in the config:", problems.len())?;
+                        for problem in problems {
+                            write!(f, "\n  - {}", problem.display(&path))?;
+                        }
+                        Ok(())
+                    }
+                }
+            }
+            Error::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            Error::Markdown { path, message } => write!(f, "{}: {}", path.display(), message),
+            Error::Assets { missing } => {
+                write!(f, "{} referenced file(s) not found:\n  - {}", missing.len(), missing.join("\n  - "))
+            }
+            Error::Plugin { name, command, message } => {
+                write!(f, "Preprocessor '{}' ({}) failed: {}", name, command, message)
+            }
+            Error::Links { broken } => {
+                write!(f, "{} link(s) could not be resolved:\n  - {}", broken.len(), broken.join("\n  - "))
+            }
+            Error::SearchIndex { message } => write!(f, "The search index could not be serialized: {}", message),
+            Error::Server { address, source } => write!(f, "Could not listen on {}: {}", address, source),
+            Error::Usage { message } => write!(f, "{}", message),
+            Error::Check { config, pages, plugins, links, .. } => match config.len() + pages.len() + plugins.len() + links.len() {
+                1 => write!(f, "1 problem found"),
+                problems => write!(f, "{} problems found", problems),
+            },
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } | Error::Server { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
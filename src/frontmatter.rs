@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// Page layouts understood by the generator
+pub const LAYOUTS: [&str; 2] = ["default", "wide"];
+
+/// Metadata from the YAML block at the top of a markdown page:
+///
+/// ```markdown
+/// ---
+/// title: Deployment
+/// description: Ship the generated site to any static host
+/// order: 2
+/// tags: [hosting, ci]
+/// ---
+/// ```
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct FrontMatter {
+    /// Navigation and page title, used when the config does not set one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Page description for the `<meta name="description">` tag and search results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Drafts are left out of the built site
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub draft: bool,
+    /// Position among sibling pages when the navigation is generated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Keep the page out of the search index
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub hide_from_search: bool,
+    /// One of [`LAYOUTS`]; `wide` drops the table of contents and uses the full width
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<String>,
+}
+
+impl FrontMatter {
+    /// Layout to render the page with, falling back to `default` for unknown names
+    pub fn layout(&self) -> &str {
+        match self.layout.as_deref() {
+            Some(layout) if LAYOUTS.contains(&layout) => layout,
+            _ => "default",
+        }
+    }
+}
+
+/// Split a markdown document into its front matter and body. Documents without a leading
+/// `---` line have empty front matter and are returned unchanged.
+pub fn split(content: &str) -> Result<(FrontMatter, &str), String> {
+    let Some((yaml, body)) = find_block(content) else {
+        return Ok((FrontMatter::default(), content));
+    };
+
+    // An empty block (`---` followed by `---`) is valid and means no metadata
+    let front_matter = if yaml.trim().is_empty() {
+        FrontMatter::default()
+    } else {
+        serde_yaml::from_str(yaml).map_err(|e| format!("invalid front matter: {}", e))?
+    };
+
+    if let Some(layout) = &front_matter.layout {
+        if !LAYOUTS.contains(&layout.as_str()) {
+            return Err(format!("unknown layout '{}' in front matter (expected one of: {})", layout, LAYOUTS.join(", ")));
+        }
+    }
+
+    Ok((front_matter, body))
+}
+
+/// Locate the YAML between the opening `---` and the closing `---` (or `...`) line
+fn find_block(content: &str) -> Option<(&str, &str)> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let rest = content.strip_prefix("---")?;
+    let rest = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n'))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == "---" || trimmed == "..." {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_without_a_closed_block_have_no_front_matter() {
+        let plain = "# Intro\n\nText\n";
+        assert_eq!(split(plain).unwrap(), (FrontMatter::default(), plain));
+
+        let unterminated = "---\ntitle: Intro\n\n# Intro\n";
+        assert_eq!(split(unterminated).unwrap(), (FrontMatter::default(), unterminated));
+
+        // A rule has to be on its own line to open a block
+        let rule = "--- not front matter\n---\n";
+        assert_eq!(find_block(rule), None);
+    }
+
+    #[test]
+    fn the_first_closing_line_ends_the_block() {
+        let (front_matter, body) = split("---\ntitle: Intro\n---\n# Intro\n\n---\n\nAfter the rule\n").unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Intro"));
+        assert_eq!(body, "# Intro\n\n---\n\nAfter the rule\n");
+
+        assert_eq!(find_block("---\norder: 2\n...\nBody"), Some(("order: 2\n", "Body")));
+        assert_eq!(split("---\n---\nBody").unwrap(), (FrontMatter::default(), "Body"));
+    }
+
+    #[test]
+    fn crlf_line_endings_and_a_byte_order_mark_are_accepted() {
+        let (front_matter, body) = split("\u{feff}---\r\ntitle: Intro\r\norder: 2\r\n---\r\n# Intro\r\n").unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Intro"));
+        assert_eq!(front_matter.order, Some(2));
+        assert_eq!(body, "# Intro\r\n");
+    }
+
+    #[test]
+    fn unknown_layouts_and_invalid_yaml_are_errors() {
+        assert_eq!(
+            split("---\nlayout: narrow\n---\n").unwrap_err(),
+            "unknown layout 'narrow' in front matter (expected one of: default, wide)"
+        );
+        assert!(split("---\ntitle: [unclosed\n---\n").unwrap_err().starts_with("invalid front matter: "));
+
+        let (front_matter, _) = split("---\nlayout: wide\n---\n").unwrap();
+        assert_eq!(front_matter.layout(), "wide");
+        assert_eq!(FrontMatter { layout: Some("narrow".to_string()), ..Default::default() }.layout(), "default");
+    }
+}
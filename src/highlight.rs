@@ -0,0 +1,694 @@
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag};
+
+/// Token kinds, styled by the `.tok-*` rules in `css::syntax`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Keyword,
+    String,
+    Comment,
+    Number,
+    Literal,
+    Type,
+    Function,
+    Property,
+    Tag,
+}
+
+impl Token {
+    fn class(self) -> &'static str {
+        match self {
+            Token::Keyword => "tok-keyword",
+            Token::String => "tok-string",
+            Token::Comment => "tok-comment",
+            Token::Number => "tok-number",
+            Token::Literal => "tok-literal",
+            Token::Type => "tok-type",
+            Token::Function => "tok-function",
+            Token::Property => "tok-property",
+            Token::Tag => "tok-tag",
+        }
+    }
+}
+
+/// How a language is tokenized
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Style {
+    /// Comments, strings, numbers and identifiers
+    Code,
+    /// Like `Code`, with property names inside `{ }` and `@` rules
+    Css,
+    /// Tags, attributes and comments of HTML and XML
+    Markup,
+}
+
+struct Language {
+    style: Style,
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    keywords: &'static [&'static str],
+    literals: &'static [&'static str],
+    types: &'static [&'static str],
+    /// Identifiers starting with an uppercase letter are types
+    capitalized_types: bool,
+    /// Keys at the start of a line followed by this separator are properties (`:` in YAML, `=` in TOML)
+    line_keys: Option<char>,
+    /// Strings followed by `:` are object keys
+    string_keys: bool,
+    /// `$name` and `${name}` are variables
+    variables: bool,
+    /// Lines starting with `[` are table headers
+    table_headers: bool,
+    /// `'a` is a lifetime rather than a character literal, and `name!` is a macro call
+    lifetimes_and_macros: bool,
+    /// Block comments nest, and `r"..."` and `r#"..."#` are raw strings
+    nested_comments_and_raw_strings: bool,
+}
+
+const CODE: Language = Language {
+    style: Style::Code,
+    line_comments: &[],
+    block_comment: None,
+    quotes: &['"', '\''],
+    keywords: &[],
+    literals: &[],
+    types: &[],
+    capitalized_types: false,
+    line_keys: None,
+    string_keys: false,
+    variables: false,
+    table_headers: false,
+    lifetimes_and_macros: false,
+    nested_comments_and_raw_strings: false,
+};
+
+const RUST: Language = Language {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "fn", "for",
+        "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+        "static", "struct", "super", "trait", "type", "unsafe", "use", "where", "while",
+    ],
+    literals: &["true", "false"],
+    types: &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+        "f32", "f64",
+    ],
+    capitalized_types: true,
+    lifetimes_and_macros: true,
+    nested_comments_and_raw_strings: true,
+    ..CODE
+};
+
+const JAVASCRIPT: Language = Language {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    keywords: &[
+        "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "delete", "do", "else",
+        "export", "extends", "finally", "for", "from", "function", "if", "import", "in", "instanceof", "let", "new",
+        "of", "return", "static", "super", "switch", "this", "throw", "try", "typeof", "var", "void", "while", "yield",
+    ],
+    literals: &["true", "false", "null", "undefined", "NaN", "Infinity"],
+    capitalized_types: true,
+    ..CODE
+};
+
+const TYPESCRIPT: Language = Language {
+    keywords: &[
+        "abstract", "as", "async", "await", "break", "case", "catch", "class", "const", "continue", "declare", "default",
+        "delete", "do", "else", "enum", "export", "extends", "finally", "for", "from", "function", "if", "implements",
+        "import", "in", "instanceof", "interface", "keyof", "let", "namespace", "new", "of", "private", "protected",
+        "public", "readonly", "return", "static", "super", "switch", "this", "throw", "try", "type", "typeof", "var",
+        "void", "while", "yield",
+    ],
+    types: &["any", "boolean", "never", "number", "object", "string", "symbol", "unknown"],
+    ..JAVASCRIPT
+};
+
+const PYTHON: Language = Language {
+    line_comments: &["#"],
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else", "except",
+        "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass",
+        "raise", "return", "try", "while", "with", "yield",
+    ],
+    literals: &["True", "False", "None"],
+    capitalized_types: true,
+    ..CODE
+};
+
+const JSON: Language = Language {
+    quotes: &['"'],
+    literals: &["true", "false", "null"],
+    string_keys: true,
+    ..CODE
+};
+
+const YAML: Language = Language {
+    line_comments: &["#"],
+    literals: &["true", "false", "null", "yes", "no", "on", "off", "~"],
+    line_keys: Some(':'),
+    ..CODE
+};
+
+const TOML: Language = Language {
+    line_comments: &["#"],
+    literals: &["true", "false"],
+    line_keys: Some('='),
+    table_headers: true,
+    ..CODE
+};
+
+const SHELL: Language = Language {
+    line_comments: &["#"],
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in", "local", "return",
+        "then", "until", "while",
+    ],
+    literals: &["true", "false"],
+    variables: true,
+    ..CODE
+};
+
+const CSS: Language = Language {
+    style: Style::Css,
+    block_comment: Some(("/*", "*/")),
+    keywords: &["!important"],
+    ..CODE
+};
+
+const MARKUP: Language = Language {
+    style: Style::Markup,
+    ..CODE
+};
+
+/// Language for the first word of a fenced code block's info string, if it is supported
+fn language(name: &str) -> Option<&'static Language> {
+    match name.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Some(&RUST),
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" => Some(&JAVASCRIPT),
+        "typescript" | "ts" | "tsx" => Some(&TYPESCRIPT),
+        "python" | "py" => Some(&PYTHON),
+        "json" | "jsonc" => Some(&JSON),
+        "yaml" | "yml" => Some(&YAML),
+        "toml" => Some(&TOML),
+        "bash" | "sh" | "shell" | "zsh" => Some(&SHELL),
+        "css" | "scss" => Some(&CSS),
+        "html" | "xml" | "svg" | "vue" => Some(&MARKUP),
+        _ => None,
+    }
+}
+
+/// Replace fenced code blocks with pre-highlighted HTML. Blocks in unsupported languages are
+/// escaped as plain text, exactly as `pulldown_cmark::html::push_html` would write them.
+pub fn highlight_code_blocks<'a>(events: impl Iterator<Item = Event<'a>>) -> Vec<Event<'a>> {
+    let mut output = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+
+    for event in events {
+        match (&mut code_block, event) {
+            (None, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info)))) => {
+                let lang = info.split_whitespace().next().unwrap_or("").to_string();
+                code_block = Some((lang, String::new()));
+            }
+            (Some((_, code)), Event::Text(text)) => code.push_str(&text),
+            (Some((lang, code)), Event::End(Tag::CodeBlock(_))) => {
+                let html = render_code_block(lang, code);
+                output.push(Event::Html(CowStr::from(html)));
+                code_block = None;
+            }
+            (_, event) => output.push(event),
+        }
+    }
+
+    output
+}
+
+fn render_code_block(lang: &str, code: &str) -> String {
+    let class = if lang.is_empty() {
+        String::new()
+    } else {
+        format!(" class=\"language-{}\"", escape_html(lang))
+    };
+
+    let body = match language(lang) {
+        Some(language) => highlight(code, language),
+        None => escape_html(code),
+    };
+
+    format!("<pre><code{}>{}</code></pre>\n", class, body)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Accumulates highlighted HTML
+struct Output {
+    html: String,
+}
+
+impl Output {
+    fn plain(&mut self, chars: &[char]) {
+        let text: String = chars.iter().collect();
+        self.html.push_str(&escape_html(&text));
+    }
+
+    fn token(&mut self, token: Token, chars: &[char]) {
+        let text: String = chars.iter().collect();
+        self.html.push_str(&format!("<span class=\"{}\">{}</span>", token.class(), escape_html(&text)));
+    }
+}
+
+fn highlight(code: &str, language: &Language) -> String {
+    let chars: Vec<char> = code.chars().collect();
+    let mut out = Output { html: String::new() };
+
+    match language.style {
+        Style::Markup => highlight_markup(&chars, &mut out),
+        Style::Code | Style::Css => highlight_code(&chars, language, &mut out),
+    }
+
+    out.html
+}
+
+fn starts_with(chars: &[char], at: usize, pattern: &str) -> bool {
+    pattern.chars().enumerate().all(|(offset, expected)| chars.get(at + offset) == Some(&expected))
+}
+
+/// Index just past the next occurrence of `pattern` at or after `from`, or the end of the input
+fn find_end(chars: &[char], from: usize, pattern: &str) -> usize {
+    (from..chars.len())
+        .find(|&i| starts_with(chars, i, pattern))
+        .map_or(chars.len(), |i| i + pattern.chars().count())
+}
+
+fn line_end(chars: &[char], from: usize) -> usize {
+    (from..chars.len()).find(|&i| chars[i] == '\n').unwrap_or(chars.len())
+}
+
+fn next_non_space(chars: &[char], from: usize) -> Option<char> {
+    chars[from..].iter().copied().find(|c| *c != ' ' && *c != '\t')
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_ident(c: char, style: Style) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || (style == Style::Css && c == '-')
+}
+
+/// End of the string starting at `start`, honouring backslash escapes and triple quotes
+fn string_end(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let triple: String = [quote; 3].iter().collect();
+    if starts_with(chars, start, &triple) {
+        return find_end(chars, start + 3, &triple);
+    }
+
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return i + 1,
+            // Only template literals span lines
+            '\n' if quote != '`' => return i,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// End of the block comment opened at `start`, counting nested comments when `nested` is set
+fn block_comment_end(chars: &[char], start: usize, (open, close): (&str, &str), nested: bool) -> usize {
+    if !nested {
+        return find_end(chars, start + open.len(), close);
+    }
+
+    let mut depth = 0;
+    let mut i = start;
+    while i < chars.len() {
+        if starts_with(chars, i, open) {
+            depth += 1;
+            i += open.len();
+        } else if starts_with(chars, i, close) {
+            depth -= 1;
+            i += close.len();
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    chars.len()
+}
+
+/// End of the Rust raw string (`r"..."`, `r#"..."#`, `br"..."`) starting at `start`, if one does
+fn raw_string_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut i = start;
+    if chars[i] == 'b' {
+        i += 1;
+    }
+    if chars.get(i) != Some(&'r') {
+        return None;
+    }
+    i += 1;
+    let hashes = (i..chars.len()).take_while(|&j| chars[j] == '#').count();
+    i += hashes;
+    if chars.get(i) != Some(&'"') {
+        return None;
+    }
+
+    let close: String = std::iter::once('"').chain(std::iter::repeat_n('#', hashes)).collect();
+    Some(find_end(chars, i + 1, &close))
+}
+
+fn highlight_code(chars: &[char], language: &Language, out: &mut Output) {
+    let mut i = 0;
+    // Only indentation (and YAML list dashes) seen since the last newline
+    let mut line_start = true;
+    let mut brace_depth = 0usize;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            out.plain(&chars[i..=i]);
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if line_start && (c == ' ' || c == '\t') {
+            out.plain(&chars[i..=i]);
+            i += 1;
+            continue;
+        }
+        let at_line_start = line_start;
+        line_start = false;
+
+        if let Some((open, close)) = language.block_comment {
+            if starts_with(chars, i, open) {
+                let end = block_comment_end(chars, i, (open, close), language.nested_comments_and_raw_strings);
+                out.token(Token::Comment, &chars[i..end]);
+                i = end;
+                continue;
+            }
+        }
+
+        // `#` only starts a comment at a word boundary, so `a#b` and URLs survive
+        let line_comment = language.line_comments.iter().any(|marker| {
+            starts_with(chars, i, marker) && (*marker != "#" || i == 0 || chars[i - 1].is_whitespace())
+        });
+        if line_comment {
+            let end = line_end(chars, i);
+            out.token(Token::Comment, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if at_line_start && language.table_headers && c == '[' {
+            let end = line_end(chars, i);
+            out.token(Token::Type, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if at_line_start {
+            if let Some(separator) = language.line_keys {
+                // YAML list items: keep looking for a key after the dash
+                if c == '-' && chars.get(i + 1) == Some(&' ') {
+                    out.plain(&chars[i..i + 2]);
+                    line_start = true;
+                    i += 2;
+                    continue;
+                }
+
+                let end = line_end(chars, i);
+                let key_end = (i..end).find(|&j| chars[j] == separator);
+                let is_key = key_end.is_some_and(|j| {
+                    let followed_by_space = chars.get(j + 1).is_none_or(|next| next.is_whitespace());
+                    j > i && !language.quotes.contains(&c) && (separator != ':' || followed_by_space)
+                        && !chars[i..j].contains(&'#')
+                });
+                if let (true, Some(key_end)) = (is_key, key_end) {
+                    let trimmed = (i..key_end).rev().find(|&j| !chars[j].is_whitespace()).map_or(i, |j| j + 1);
+                    out.token(Token::Property, &chars[i..trimmed]);
+                    i = trimmed;
+                    continue;
+                }
+            }
+        }
+
+        // Rust lifetimes (`'a`) are not character literals
+        let lifetime = language.lifetimes_and_macros
+            && c == '\''
+            && chars.get(i + 1).is_some_and(|next| is_ident_start(*next))
+            && chars.get(i + 2) != Some(&'\'');
+        if language.quotes.contains(&c) && !lifetime {
+            let end = string_end(chars, i);
+            let token = if language.string_keys && next_non_space(chars, end) == Some(':') {
+                Token::Property
+            } else {
+                Token::String
+            };
+            out.token(token, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        let after_ident = i > 0 && is_ident(chars[i - 1], language.style);
+        if language.nested_comments_and_raw_strings && !after_ident {
+            if let Some(end) = raw_string_end(chars, i) {
+                out.token(Token::String, &chars[i..end]);
+                i = end;
+                continue;
+            }
+        }
+
+        if c.is_ascii_digit() && !(i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_')) {
+            let mut end = i + 1;
+            while end < chars.len()
+                && (chars[end].is_alphanumeric() || chars[end] == '_'
+                    || (chars[end] == '.' && chars.get(end + 1).is_some_and(|next| next.is_ascii_digit())))
+            {
+                end += 1;
+            }
+            out.token(Token::Number, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        if language.variables && c == '$' {
+            let end = if chars.get(i + 1) == Some(&'{') {
+                find_end(chars, i + 2, "}")
+            } else {
+                (i + 1..chars.len()).find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_')).unwrap_or(chars.len())
+            };
+            if end > i + 1 {
+                out.token(Token::Property, &chars[i..end]);
+                i = end;
+                continue;
+            }
+        }
+
+        if language.style == Style::Css {
+            match c {
+                '{' => brace_depth += 1,
+                '}' => brace_depth = brace_depth.saturating_sub(1),
+                '@' | '!' => {
+                    let end = (i + 1..chars.len()).find(|&j| !is_ident(chars[j], Style::Css)).unwrap_or(chars.len());
+                    if end > i + 1 {
+                        let token = if c == '@' || language.keywords.contains(&chars[i..end].iter().collect::<String>().as_str()) {
+                            Token::Keyword
+                        } else {
+                            Token::Literal
+                        };
+                        out.token(token, &chars[i..end]);
+                        i = end;
+                        continue;
+                    }
+                }
+                // Hex colors
+                '#' if brace_depth > 0 => {
+                    let end = (i + 1..chars.len()).find(|&j| !chars[j].is_ascii_hexdigit()).unwrap_or(chars.len());
+                    out.token(Token::Number, &chars[i..end]);
+                    i = end;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if is_ident_start(c) && !after_ident {
+            let mut end = i + 1;
+            while end < chars.len() && is_ident(chars[end], language.style) {
+                end += 1;
+            }
+            let word: String = chars[i..end].iter().collect();
+            let next = next_non_space(chars, end);
+
+            let token = if language.style == Style::Css {
+                // Only property names are colored; selectors and values stay plain
+                (brace_depth > 0 && next == Some(':')).then_some(Token::Property)
+            } else if language.keywords.contains(&word.as_str()) {
+                Some(Token::Keyword)
+            } else if language.literals.contains(&word.as_str()) {
+                Some(Token::Literal)
+            } else if language.types.contains(&word.as_str()) || (language.capitalized_types && c.is_uppercase()) {
+                Some(Token::Type)
+            } else if chars.get(end) == Some(&'(') || (language.lifetimes_and_macros && chars.get(end) == Some(&'!')) {
+                Some(Token::Function)
+            } else {
+                None
+            };
+
+            match token {
+                Some(token) => out.token(token, &chars[i..end]),
+                None => out.plain(&chars[i..end]),
+            }
+            i = end;
+            continue;
+        }
+
+        // Literals made of symbols, like YAML's `~`
+        if language.literals.iter().any(|literal| literal.len() == 1 && literal.starts_with(c)) && !after_ident {
+            out.token(Token::Literal, &chars[i..=i]);
+            i += 1;
+            continue;
+        }
+
+        out.plain(&chars[i..=i]);
+        i += 1;
+    }
+}
+
+fn highlight_markup(chars: &[char], out: &mut Output) {
+    let mut i = 0;
+
+    while i < chars.len() {
+        if starts_with(chars, i, "<!--") {
+            let end = find_end(chars, i + 4, "-->");
+            out.token(Token::Comment, &chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        let opens_tag = chars[i] == '<'
+            && chars.get(i + 1).is_some_and(|next| next.is_alphabetic() || *next == '/' || *next == '!' || *next == '?');
+        if !opens_tag {
+            let end = (i + 1..chars.len()).find(|&j| chars[j] == '<').unwrap_or(chars.len());
+            out.plain(&chars[i..end]);
+            i = end;
+            continue;
+        }
+
+        // `<`, `</`, `<!` or `<?` followed by the tag name
+        let name_start = if chars[i + 1].is_alphabetic() { i + 1 } else { i + 2 };
+        out.plain(&chars[i..name_start]);
+        let name_end = (name_start..chars.len())
+            .find(|&j| !(chars[j].is_alphanumeric() || matches!(chars[j], '-' | ':' | '_' | '.')))
+            .unwrap_or(chars.len());
+        out.token(Token::Tag, &chars[name_start..name_end]);
+        i = name_end;
+
+        // Attributes up to the closing `>`
+        while i < chars.len() && chars[i] != '>' {
+            let c = chars[i];
+            if c == '"' || c == '\'' {
+                let end = (i + 1..chars.len()).find(|&j| chars[j] == c).map_or(chars.len(), |j| j + 1);
+                out.token(Token::String, &chars[i..end]);
+                i = end;
+            } else if c.is_alphabetic() || c == '@' || c == ':' {
+                let end = (i + 1..chars.len())
+                    .find(|&j| !(chars[j].is_alphanumeric() || matches!(chars[j], '-' | ':' | '_' | '.' | '@')))
+                    .unwrap_or(chars.len());
+                out.token(Token::Property, &chars[i..end]);
+                i = end;
+            } else {
+                out.plain(&chars[i..=i]);
+                i += 1;
+            }
+        }
+        if i < chars.len() {
+            out.plain(&chars[i..=i]);
+            i += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `(class, text)` of every highlighted token in `code`
+    fn tokens(lang: &str, code: &str) -> Vec<(String, String)> {
+        let html = render_code_block(lang, code);
+        let span = regex::Regex::new(r#"<span class="(tok-[a-z]+)">([^<]*)</span>"#).unwrap();
+        span.captures_iter(&html)
+            .map(|caps| {
+                let text = caps[2].replace("&quot;", "\"").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&");
+                (caps[1].to_string(), text)
+            })
+            .collect()
+    }
+
+    fn token(class: &str, text: &str) -> (String, String) {
+        (class.to_string(), text.to_string())
+    }
+
+    #[test]
+    fn strings_keep_escaped_quotes() {
+        assert_eq!(tokens("js", r#"say("a \"b\" c", 'it\'s')"#), [
+            token("tok-function", "say"),
+            token("tok-string", r#""a \"b\" c""#),
+            token("tok-string", r"'it\'s'"),
+        ]);
+        // An unterminated string ends with its line
+        assert_eq!(tokens("py", "x = 'open\ny = 1"), [token("tok-string", "'open"), token("tok-number", "1")]);
+    }
+
+    #[test]
+    fn rust_block_comments_nest() {
+        assert_eq!(tokens("rust", "/* a /* b */ c */ fn"), [
+            token("tok-comment", "/* a /* b */ c */"),
+            token("tok-keyword", "fn"),
+        ]);
+        // Other languages end the comment at the first `*/`
+        assert_eq!(tokens("js", "/* a /* b */ c */")[0], token("tok-comment", "/* a /* b */"));
+    }
+
+    #[test]
+    fn rust_raw_strings_end_at_their_hashes() {
+        assert_eq!(tokens("rust", r###"let s = r#"say "hi""# + br"\d";"###), [
+            token("tok-keyword", "let"),
+            token("tok-string", r##"r#"say "hi""#"##),
+            token("tok-string", r#"br"\d""#),
+        ]);
+        // `r` alone is an identifier
+        assert_eq!(tokens("rust", "r + 1"), [token("tok-number", "1")]);
+    }
+
+    #[test]
+    fn numbers_are_not_taken_from_identifiers() {
+        assert_eq!(tokens("rust", "let x1 = 0xff + 1.5 + 1_000u32;"), [
+            token("tok-keyword", "let"),
+            token("tok-number", "0xff"),
+            token("tok-number", "1.5"),
+            token("tok-number", "1_000u32"),
+        ]);
+        assert_eq!(tokens("rust", "0..2"), [token("tok-number", "0"), token("tok-number", "2")]);
+    }
+
+    #[test]
+    fn unknown_languages_are_escaped_as_plain_text() {
+        assert_eq!(render_code_block("brainfuck", "<a> & \"b\""), "<pre><code class=\"language-brainfuck\">&lt;a&gt; &amp; &quot;b&quot;</code></pre>\n");
+        assert_eq!(render_code_block("", "fn x"), "<pre><code>fn x</code></pre>\n");
+    }
+}
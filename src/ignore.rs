@@ -0,0 +1,251 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use regex::Regex;
+use crate::error::{self, Error, Result};
+use crate::{Diagnostic, Severity};
+
+/// File in the docs folder listing files GlowDoc leaves out, one pattern per line
+pub const IGNORE_FILE: &str = ".glowdocignore";
+
+/// Files and folders of the docs folder that are left out of config detection, orphan
+/// detection, asset collection and the watcher: the patterns of [`IGNORE_FILE`] followed by
+/// the `exclude:` globs of the config. Patterns use `.gitignore` syntax: `*` and `?` stay
+/// within a folder, `**` spans folders, a trailing `/` matches only folders, a pattern with a
+/// `/` elsewhere is relative to the docs folder, and `!` re-includes what an earlier pattern
+/// left out.
+#[derive(Debug, Default)]
+pub struct Ignore {
+    docs_path: PathBuf,
+    /// `docs_path` resolved, to match the absolute paths reported by the watcher
+    canonical_docs_path: Option<PathBuf>,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug)]
+struct Rule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+impl Ignore {
+    /// The patterns of the docs folder's [`IGNORE_FILE`], when it has one, and `exclude`.
+    /// Invalid patterns in the ignore file are an [`Error::Config`] pointing at their line;
+    /// invalid `exclude` globs are left out, as validating the config reports them.
+    pub fn load<P: AsRef<Path>>(docs_path: P, exclude: &[String]) -> Result<Self> {
+        let docs_path = docs_path.as_ref();
+        let ignore_file = docs_path.join(IGNORE_FILE);
+        let content = if ignore_file.is_file() {
+            fs::read_to_string(&ignore_file).map_err(error::io(&ignore_file))?
+        } else {
+            String::new()
+        };
+
+        let mut rules = Vec::new();
+        let mut problems = Vec::new();
+        for (number, line) in content.lines().enumerate() {
+            match Rule::parse(line) {
+                Ok(rule) => rules.extend(rule),
+                Err(message) => problems.push(Diagnostic {
+                    severity: Severity::Error,
+                    position: Some((number + 1, line.len() - line.trim_start().len() + 1)),
+                    message,
+                }),
+            }
+        }
+        if !problems.is_empty() {
+            return Err(Error::Config { path: ignore_file, problems });
+        }
+        rules.extend(exclude.iter().filter_map(|pattern| Rule::parse(pattern).ok().flatten()));
+
+        Ok(Ignore {
+            docs_path: docs_path.to_path_buf(),
+            canonical_docs_path: fs::canonicalize(docs_path).ok(),
+            rules,
+        })
+    }
+
+    /// Whether `path`, inside the docs folder, or a folder containing it is ignored. Paths
+    /// outside the docs folder never are.
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let relative = path.strip_prefix(&self.docs_path).ok()
+            .or_else(|| path.strip_prefix(self.canonical_docs_path.as_ref()?).ok());
+        match relative {
+            Some(relative) => self.is_ignored_relative(relative, path.is_dir()),
+            None => false,
+        }
+    }
+
+    /// Whether `relative`, a path relative to the docs folder, or a folder containing it is
+    /// ignored
+    pub fn is_ignored_relative(&self, relative: &Path, is_dir: bool) -> bool {
+        let parts: Vec<&str> = relative.iter().filter_map(|part| part.to_str()).collect();
+        (1..=parts.len()).any(|len| {
+            let is_folder = len < parts.len() || is_dir;
+            self.matches(&parts[..len].join("/"), is_folder)
+        })
+    }
+
+    /// Whether the last rule matching `path` ignores it
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        self.rules.iter()
+            .rev()
+            .find(|rule| (is_dir || !rule.dir_only) && rule.regex.is_match(path))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
+
+/// Why `pattern` cannot be used as an ignore pattern, if it cannot
+pub fn invalid_pattern(pattern: &str) -> Option<String> {
+    Rule::parse(pattern).err()
+}
+
+impl Rule {
+    /// A rule for one line of an ignore file, or `None` for blank lines and comments. Fails
+    /// for patterns that do not translate into a regex, such as `[z-a].md`.
+    fn parse(line: &str) -> std::result::Result<Option<Rule>, String> {
+        let pattern = line.trim();
+        if pattern.is_empty() || pattern.starts_with('#') {
+            return Ok(None);
+        }
+        let original = pattern;
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        let (dir_only, pattern) = match pattern.strip_suffix('/') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        // Without a `/`, a pattern matches a file or folder name at any depth
+        let prefix = if pattern.contains('/') { "^" } else { "^(?:.*/)?" };
+        let pattern = pattern.trim_start_matches('/');
+
+        let regex = Regex::new(&format!("{}{}$", prefix, glob_to_regex(pattern))).map_err(|e| {
+            // The last line of a syntax error names the problem, e.g. `error: invalid character class range`
+            let reason = e.to_string();
+            let reason = reason.lines().last().unwrap_or_default().trim().trim_start_matches("error: ").to_string();
+            format!("invalid pattern '{}': {}", original, reason)
+        })?;
+        Ok(Some(Rule { regex, negated, dir_only }))
+    }
+}
+
+/// Translate a glob into a regex matching `/`-separated relative paths
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let class: String = chars.clone().take_while(|&c| c != ']').collect();
+                if class.is_empty() || chars.clone().nth(class.chars().count()) != Some(']') {
+                    regex.push_str(r"\[");
+                    continue;
+                }
+                for _ in 0..=class.chars().count() {
+                    chars.next();
+                }
+                let class = match class.strip_prefix('!') {
+                    Some(rest) => format!("^{}", rest),
+                    None => class,
+                };
+                regex.push('[');
+                regex.push_str(&class.replace('\\', r"\\").replace('[', r"\["));
+                regex.push(']');
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+/// Whether `path` is a swap, backup or lock file written by an editor, which the watcher does
+/// not rebuild for
+pub fn is_editor_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    name.ends_with('~')
+        || name.starts_with(".#")
+        || (name.starts_with('#') && name.ends_with('#'))
+        || [".swp", ".swo", ".swx", ".tmp", ".bak", ".kate-swp"].iter().any(|extension| name.ends_with(extension))
+        // Vim checks that it can write to a folder by creating this file
+        || name == "4913"
+        || name == ".DS_Store"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignore(patterns: &[&str]) -> Ignore {
+        Ignore {
+            rules: patterns.iter().filter_map(|pattern| Rule::parse(pattern).unwrap()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn patterns_follow_gitignore_rules() {
+        let ignore = ignore(&["# drafts", "README.md", "/vendor/", "guide/**/old-*.md", "*.draft.md", "!keep.draft.md", "notes/"]);
+        let ignored = |path: &str| ignore.is_ignored_relative(Path::new(path), false);
+
+        assert!(ignored("README.md"));
+        assert!(ignored("guide/README.md"));
+        assert!(ignored("vendor/lib/intro.md"));
+        assert!(!ignored("guide/vendor/intro.md"));
+        assert!(ignored("guide/old-setup.md"));
+        assert!(ignored("guide/deep/old-setup.md"));
+        assert!(!ignored("api/old-setup.md"));
+        assert!(ignored("guide/ideas.draft.md"));
+        assert!(!ignored("guide/keep.draft.md"));
+        assert!(ignored("guide/notes/todo.md"));
+        assert!(!ignored("guide/notes"));
+        assert!(ignore.is_ignored_relative(Path::new("guide/notes"), true));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported_with_their_line() {
+        assert_eq!(invalid_pattern("guide/[z-a].md").as_deref(), Some("invalid pattern 'guide/[z-a].md': invalid character class range, the start must be <= the end"));
+        assert_eq!(invalid_pattern("*.draft.md"), None);
+
+        let docs = std::env::temp_dir().join(format!("glowdoc-ignore-invalid-{}", std::process::id()));
+        fs::create_dir_all(&docs).unwrap();
+        fs::write(docs.join(IGNORE_FILE), "# drafts\n*.draft.md\n  [z-a]*\n").unwrap();
+        let result = Ignore::load(&docs, &["[b-a]".to_string()]);
+        fs::remove_dir_all(&docs).unwrap();
+
+        match result {
+            Err(Error::Config { path, problems }) => {
+                assert_eq!(path, docs.join(IGNORE_FILE));
+                assert_eq!(problems.len(), 1);
+                assert_eq!(problems[0].position, Some((3, 3)));
+                assert!(problems[0].message.starts_with("invalid pattern '[z-a]*'"), "{}", problems[0].message);
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn editor_files_are_recognized() {
+        for name in ["page.md~", ".page.md.swp", ".#page.md", "#page.md#", "4913"] {
+            assert!(is_editor_file(Path::new(name)), "{}", name);
+        }
+        assert!(!is_editor_file(Path::new("guide/page.md")));
+    }
+}
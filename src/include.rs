@@ -0,0 +1,223 @@
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use regex::{Captures, Regex};
+
+/// Markdown with its `{{#include}}` directives replaced by the text they name
+#[derive(Debug, Default)]
+pub struct Expanded {
+    pub source: String,
+    /// Every file read for the directives, including those of included markdown files
+    pub dependencies: Vec<PathBuf>,
+    /// Where the text of each directive ended up in `source`, with how many lines it added
+    pub spans: Vec<(Range<usize>, usize)>,
+}
+
+/// Expand the include directives of `source`, the content of the file at `path`:
+///
+/// - `{{#include file}}`: the whole file
+/// - `{{#include file:10}}`: line 10
+/// - `{{#include file:10:40}}`, `file:10:` and `file::40`: a range of lines, 1-based and inclusive
+/// - `{{#include file:setup}}`: the lines between `ANCHOR: setup` and `ANCHOR_END: setup`
+///
+/// Paths are relative to the including file. Included markdown files are expanded in turn;
+/// `\{{#include ...}}` is left as written, without the backslash.
+pub fn expand(path: &Path, source: &str) -> Result<Expanded, String> {
+    let mut expanded = Expanded::default();
+    let mut stack = vec![fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())];
+    expanded.source = expand_directives(path, source, &mut stack, &mut expanded.dependencies, Some(&mut expanded.spans))?;
+    Ok(expanded)
+}
+
+fn expand_directives(
+    path: &Path,
+    source: &str,
+    stack: &mut Vec<PathBuf>,
+    dependencies: &mut Vec<PathBuf>,
+    mut spans: Option<&mut Vec<(Range<usize>, usize)>>,
+) -> Result<String, String> {
+    static DIRECTIVE: OnceLock<Regex> = OnceLock::new();
+    let directive = DIRECTIVE.get_or_init(|| Regex::new(r"(\\)?\{\{#include[ \t]+([^}\n]+?)[ \t]*\}\}").unwrap());
+
+    let mut output = String::with_capacity(source.len());
+    let mut last = 0;
+    for caps in directive.captures_iter(source) {
+        let whole = caps.get(0).unwrap();
+        output.push_str(&source[last..whole.start()]);
+        last = whole.end();
+
+        if caps.get(1).is_some() {
+            output.push_str(&whole.as_str()[1..]);
+            continue;
+        }
+
+        let start = output.len();
+        output.push_str(&include(path, &caps, stack, dependencies)?);
+        if let Some(spans) = spans.as_deref_mut() {
+            let added_lines = output[start..].matches('\n').count();
+            spans.push((start..output.len(), added_lines));
+        }
+    }
+    output.push_str(&source[last..]);
+
+    Ok(output)
+}
+
+/// The text one directive stands for
+fn include(from: &Path, caps: &Captures, stack: &mut Vec<PathBuf>, dependencies: &mut Vec<PathBuf>) -> Result<String, String> {
+    let argument = &caps[2];
+    let (file, selector) = match argument.split_once(':') {
+        Some((file, selector)) => (file, Some(selector)),
+        None => (argument, None),
+    };
+
+    let target = from.parent().unwrap_or(Path::new("")).join(file);
+    let content = fs::read_to_string(&target)
+        .map_err(|e| format!("cannot include '{}': {}", file, e))?;
+    let canonical = fs::canonicalize(&target).unwrap_or_else(|_| target.clone());
+    if !dependencies.contains(&canonical) {
+        dependencies.push(canonical.clone());
+    }
+
+    let text = match selector {
+        None => content.strip_suffix('\n').unwrap_or(&content).trim_end_matches('\r').to_string(),
+        Some(selector) => select(&content, selector).map_err(|e| format!("cannot include '{}': {}", argument, e))?,
+    };
+
+    // Markdown is expanded in turn; other files are included verbatim
+    if target.extension().is_some_and(|extension| extension == "md") {
+        if stack.contains(&canonical) {
+            let chain: Vec<String> = stack.iter().chain([&canonical]).map(|path| path.display().to_string()).collect();
+            return Err(format!("include cycle: {}", chain.join(" -> ")));
+        }
+        stack.push(canonical);
+        let expanded = expand_directives(&target, &text, stack, dependencies, None);
+        stack.pop();
+        return expanded;
+    }
+
+    Ok(text)
+}
+
+/// Lines of `content` picked by `N`, `N:M`, `N:`, `:M` or an anchor name
+fn select(content: &str, selector: &str) -> Result<String, String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let number = |text: &str| -> Result<Option<usize>, String> {
+        match text.trim() {
+            "" => Ok(None),
+            text => text.parse::<usize>()
+                .ok()
+                .filter(|line| *line > 0)
+                .map(Some)
+                .ok_or_else(|| format!("'{}' is not a line number", text)),
+        }
+    };
+
+    let range = match selector.split_once(':') {
+        Some((first, last)) => number(first)?.unwrap_or(1)..=number(last)?.unwrap_or(lines.len()),
+        None if !selector.trim().is_empty() && selector.trim().chars().all(|c| c.is_ascii_digit()) => {
+            let line = number(selector)?.unwrap_or(1);
+            line..=line
+        }
+        None => return anchored(&lines, selector.trim()),
+    };
+
+    if range.start() > range.end() {
+        return Err(format!("line {} comes after line {}", range.start(), range.end()));
+    }
+    if *range.start() > lines.len() {
+        return Err(format!("the file has only {} lines", lines.len()));
+    }
+    let end = (*range.end()).min(lines.len());
+    Ok(lines[range.start() - 1..end].join("\n"))
+}
+
+/// Lines between `ANCHOR: name` and `ANCHOR_END: name`, without the anchor lines of this or
+/// any nested region
+fn anchored(lines: &[&str], name: &str) -> Result<String, String> {
+    static ANCHOR: OnceLock<Regex> = OnceLock::new();
+    let anchor = ANCHOR.get_or_init(|| Regex::new(r"ANCHOR(_END)?:\s*([\w-]+)").unwrap());
+    let is = |line: &str, end: bool| anchor.captures(line)
+        .is_some_and(|caps| caps.get(1).is_some() == end && &caps[2] == name);
+
+    let start = lines.iter().position(|line| is(line, false))
+        .ok_or_else(|| format!("no 'ANCHOR: {}' in the file", name))?;
+    let end = lines[start + 1..].iter().position(|line| is(line, true))
+        .ok_or_else(|| format!("no 'ANCHOR_END: {}' after 'ANCHOR: {}'", name, name))?;
+
+    Ok(lines[start + 1..start + 1 + end].iter()
+        .filter(|line| !anchor.is_match(line))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &str = "\
+fn main() {
+    // ANCHOR: setup
+    let config = load();
+    // ANCHOR: inner
+    check(&config);
+    // ANCHOR_END: inner
+    // ANCHOR_END: setup
+    run(config);
+}
+";
+
+    /// A folder holding `files`, given as `(path, content)`
+    fn folder(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("glowdoc-include-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (path, content) in files {
+            fs::write(dir.join(path), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn line_ranges_are_inclusive_and_checked() {
+        assert_eq!(select(CODE, "3").unwrap(), "    let config = load();");
+        assert_eq!(select(CODE, "8:9").unwrap(), "    run(config);\n}");
+        assert_eq!(select(CODE, "8:").unwrap(), "    run(config);\n}");
+        assert_eq!(select(CODE, ":1").unwrap(), "fn main() {");
+        assert_eq!(select(CODE, "9:40").unwrap(), "}");
+        assert_eq!(select(CODE, "8:3").unwrap_err(), "line 8 comes after line 3");
+        assert_eq!(select(CODE, "40").unwrap_err(), "the file has only 9 lines");
+        assert_eq!(select(CODE, "0:2").unwrap_err(), "'0' is not a line number");
+    }
+
+    #[test]
+    fn anchors_select_their_region_without_anchor_lines() {
+        assert_eq!(select(CODE, "setup").unwrap(), "    let config = load();\n    check(&config);");
+        assert_eq!(select(CODE, "inner").unwrap(), "    check(&config);");
+        assert_eq!(select(CODE, "teardown").unwrap_err(), "no 'ANCHOR: teardown' in the file");
+    }
+
+    #[test]
+    fn directives_expand_recursively_and_report_cycles() {
+        let dir = folder("expand", &[
+            ("main.rs", CODE),
+            ("part.md", "Part {{#include main.rs:3}}\n"),
+            ("a.md", "{{#include b.md}}\n"),
+            ("b.md", "{{#include a.md}}\n"),
+        ]);
+        let expanded = expand(&dir.join("page.md"), "{{#include part.md}}\n\\{{#include part.md}}\n");
+        let cycle = expand(&dir.join("a.md"), "{{#include b.md}}\n");
+        let missing = expand(&dir.join("page.md"), "{{#include gone.md}}\n");
+        let reversed = expand(&dir.join("page.md"), "{{#include main.rs:40:10}}\n");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let expanded = expanded.unwrap();
+        assert_eq!(expanded.source, "Part     let config = load();\n{{#include part.md}}\n");
+        assert_eq!(expanded.dependencies.len(), 2);
+        assert!(cycle.unwrap_err().starts_with("include cycle: "));
+        assert!(missing.unwrap_err().starts_with("cannot include 'gone.md': "));
+        assert_eq!(reversed.unwrap_err(), "cannot include 'main.rs:40:10': line 40 comes after line 10");
+    }
+}
@@ -1,4 +1,6 @@
-pub fn generate_javascript(enable_hot_reload: bool) -> String {
+use crate::OutputMode;
+
+pub fn generate_javascript(enable_hot_reload: bool, mode: OutputMode, site_root: &str) -> String {
     let mut js = String::new();
     
     if enable_hot_reload {
@@ -46,6 +48,13 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
         "#);
     }
     
+    js.push_str(&format!(r#"
+        // Single-file output routes through the URL hash; multi-page output links to one
+        // file per page, relative to siteRoot
+        const multiPage = {};
+        const siteRoot = '{}';
+"#, mode == OutputMode::MultiPage, site_root));
+    
     js.push_str(r#"
         function toggleTheme() {
             const html = document.documentElement;
@@ -79,6 +88,11 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
         }
 
         function showHomepage() {
+            if (multiPage) {
+                window.location.href = siteRoot + 'index.html';
+                return;
+            }
+            
             document.getElementById('homepage').classList.add('active');
             document.getElementById('docs-layout').classList.remove('active');
             
@@ -90,13 +104,35 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
         }
 
         function showDocs() {
-            document.getElementById('homepage').classList.remove('active');
+            // Multi-page documents other than the homepage have no homepage element
+            const homepage = document.getElementById('homepage');
+            if (homepage) {
+                homepage.classList.remove('active');
+            }
             document.getElementById('docs-layout').classList.add('active');
         }
 
+        function pageUrl(contentId, headerId) {
+            if (multiPage) {
+                return window.location.pathname + (headerId ? '#' + headerId : '');
+            }
+            return headerId ?
+                window.location.pathname + '#' + contentId + '#' + headerId :
+                window.location.pathname + '#' + contentId;
+        }
+
         function showContent(contentId, updateUrl = true, headerId = null) {
             console.log('showContent called with contentId:', contentId, 'headerId:', headerId);
             
+            // In multi-page output every other page lives in its own file
+            if (multiPage) {
+                const currentContent = document.querySelector('.content-section.active');
+                if (!currentContent || currentContent.id !== contentId) {
+                    window.location.href = siteRoot + contentId + '/index.html' + (headerId ? '#' + headerId : '');
+                    return;
+                }
+            }
+            
             // Switch to docs view first
             showDocs();
             
@@ -133,9 +169,7 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
                 
                 // Update URL if requested
                 if (updateUrl) {
-                    const newUrl = headerId ? 
-                        window.location.pathname + '#' + contentId + '#' + headerId :
-                        window.location.pathname + '#' + contentId;
+                    const newUrl = pageUrl(contentId, headerId);
                     history.pushState({ contentId: contentId, headerId: headerId }, '', newUrl);
                 }
             } else {
@@ -233,7 +267,7 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
                         const currentContent = document.querySelector('.content-section.active');
                         if (currentContent) {
                             const contentId = currentContent.id;
-                            const newUrl = window.location.pathname + '#' + contentId + '#' + headerId;
+                            const newUrl = pageUrl(contentId, headerId);
                             history.pushState({ contentId: contentId, headerId: headerId }, '', newUrl);
                         }
                     }
@@ -313,7 +347,7 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
                     const currentContent = document.querySelector('.content-section.active');
                     if (currentContent) {
                         const contentId = currentContent.id;
-                        const newUrl = window.location.pathname + '#' + contentId + '#' + activeHeader;
+                        const newUrl = pageUrl(contentId, activeHeader);
                         
                         // Use replaceState to avoid creating browser history entries for scroll events
                         history.replaceState({ contentId: contentId, headerId: activeHeader }, '', newUrl);
@@ -367,119 +401,373 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
             }
         }
 
-        function performSearch() {
+        // The search index. Single-file sites inline it as `searchIndex`; other sites point
+        // `searchIndexUrl` at a manifest of gzipped JSON shards, fetched when search is first used.
+        let searchManifest = null;
+        const searchFiles = new Map();
+        
+        function fetchSearchFile(path) {
+            if (!searchFiles.has(path)) {
+                const loading = fetch(siteRoot + path).then(async response => {
+                    if (!response.ok) throw new Error('Could not load ' + path + ': ' + response.status);
+                    const bytes = new Uint8Array(await response.arrayBuffer());
+                    // Servers sending the file with a gzip Content-Encoding hand over plain JSON
+                    if (bytes[0] === 0x1f && bytes[1] === 0x8b) {
+                        const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+                        return JSON.parse(await new Response(stream).text());
+                    }
+                    return JSON.parse(new TextDecoder().decode(bytes));
+                });
+                // Let a failed download be retried by the next search
+                loading.catch(() => searchFiles.delete(path));
+                searchFiles.set(path, loading);
+            }
+            return searchFiles.get(path);
+        }
+        
+        // Pages and the sorted term list, plus where to find postings and records
+        function loadSearchManifest() {
+            if (!searchManifest) {
+                if (typeof searchIndex !== 'undefined') {
+                    searchManifest = Promise.resolve({ pages: searchIndex.pages, terms: Object.keys(searchIndex.terms) });
+                } else {
+                    searchManifest = fetchSearchFile(searchIndexUrl);
+                    searchManifest.catch(() => { searchManifest = null; });
+                }
+            }
+            return searchManifest;
+        }
+        
+        // `[record, weight]` pairs of the term at `position` in the manifest's term list
+        async function termPostings(manifest, position) {
+            const term = manifest.terms[position];
+            if (!manifest.term_shards) return searchIndex.terms[term];
+            const shard = await fetchSearchFile(manifest.term_shards[Math.floor(position / manifest.terms_per_shard)]);
+            return shard[term];
+        }
+        
+        async function searchRecord(manifest, recordIndex) {
+            if (!manifest.record_shards) return searchIndex.records[recordIndex];
+            const shard = await fetchSearchFile(manifest.record_shards[Math.floor(recordIndex / manifest.records_per_shard)]);
+            return shard[recordIndex % manifest.records_per_shard];
+        }
+
+        // Lowercase words, split like `search::tokenize` in the generator. Query words may be a
+        // single character, which only matches as a prefix.
+        function tokenize(text) {
+            return text.toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(word => word.length > 0);
+        }
+        
+        // Levenshtein distance between two words, giving up once it exceeds max
+        function editDistance(a, b, max) {
+            if (Math.abs(a.length - b.length) > max) return max + 1;
+            let previous = Array.from({ length: b.length + 1 }, (_, i) => i);
+            for (let i = 1; i <= a.length; i++) {
+                const current = [i];
+                let rowMin = i;
+                for (let j = 1; j <= b.length; j++) {
+                    const cost = a[i - 1] === b[j - 1] ? 0 : 1;
+                    current[j] = Math.min(previous[j] + 1, current[j - 1] + 1, previous[j - 1] + cost);
+                    rowMin = Math.min(rowMin, current[j]);
+                }
+                if (rowMin > max) return max + 1;
+                previous = current;
+            }
+            return previous[b.length];
+        }
+        
+        // Positions of the index terms matching a query word, with how much a match counts: the
+        // word itself, longer words it starts, and words one typo away (two for long words)
+        function matchingTerms(terms, word) {
+            const maxTypos = word.length >= 8 ? 2 : word.length >= 4 ? 1 : 0;
+            const matches = [];
+            terms.forEach((term, position) => {
+                if (term === word) {
+                    matches.push([position, 1]);
+                } else if (term.startsWith(word)) {
+                    matches.push([position, 0.7]);
+                } else if (maxTypos > 0 && editDistance(word, term, maxTypos) <= maxTypos) {
+                    matches.push([position, 0.4]);
+                }
+            });
+            return matches;
+        }
+        
+        // Records matching every query word, best first. A record scores the sum over the
+        // words of its best weighted match.
+        async function searchRecords(manifest, words) {
+            let scores = null;
+            for (const word of words) {
+                const matches = matchingTerms(manifest.terms, word);
+                const postings = await Promise.all(matches.map(([position]) => termPostings(manifest, position)));
+                
+                const wordScores = new Map();
+                matches.forEach(([, factor], i) => {
+                    for (const [record, weight] of postings[i]) {
+                        wordScores.set(record, Math.max(wordScores.get(record) || 0, weight * factor));
+                    }
+                });
+                
+                if (scores === null) {
+                    scores = wordScores;
+                } else {
+                    const combined = new Map();
+                    for (const [record, score] of scores) {
+                        if (wordScores.has(record)) {
+                            combined.set(record, score + wordScores.get(record));
+                        }
+                    }
+                    scores = combined;
+                }
+            }
+            
+            return Array.from(scores || [])
+                .sort((a, b) => b[1] - a[1] || a[0] - b[0])
+                .slice(0, 20)
+                .map(([record]) => record);
+        }
+        
+        // Text around the first query word found in the record
+        function searchSnippet(text, words) {
+            const textLower = text.toLowerCase();
+            const matchIndex = words
+                .map(word => textLower.indexOf(word))
+                .filter(index => index >= 0)
+                .reduce((first, index) => Math.min(first, index), Infinity);
+            if (matchIndex === Infinity) {
+                return text.length > 120 ? text.substring(0, 120) + '...' : text;
+            }
+            
+            const start = Math.max(0, matchIndex - 60);
+            const end = Math.min(text.length, matchIndex + 80);
+            let snippet = text.substring(start, end);
+            
+            // Add ellipsis if we're in the middle of the content
+            if (start > 0) snippet = '...' + snippet;
+            if (end < text.length) snippet = snippet + '...';
+            return snippet;
+        }
+
+        function escapeRegExp(text) {
+            return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+        }
+        
+        // Append `text` to `element` as text nodes, wrapping every occurrence of a query word in
+        // <mark>. Longer words are tried first, so `config` wins over `conf`.
+        function appendHighlighted(element, text, words) {
+            const alternatives = [...words].sort((a, b) => b.length - a.length).map(escapeRegExp);
+            const pattern = new RegExp(alternatives.join('|'), 'giu');
+            let end = 0;
+            for (const match of text.matchAll(pattern)) {
+                element.appendChild(document.createTextNode(text.substring(end, match.index)));
+                const mark = document.createElement('mark');
+                mark.textContent = match[0];
+                element.appendChild(mark);
+                end = match.index + match[0].length;
+            }
+            element.appendChild(document.createTextNode(text.substring(end)));
+        }
+        
+        // Replace the results with a single message
+        function showSearchMessage(searchResultsList, message) {
+            const messageDiv = document.createElement('div');
+            messageDiv.className = 'no-results';
+            messageDiv.textContent = message;
+            searchResultsList.replaceChildren(messageDiv);
+        }
+        
+        // Bumped by every search, so results of an earlier query still loading are dropped
+        let searchGeneration = 0;
+        
+        async function performSearch() {
+            const generation = ++searchGeneration;
             const searchInput = document.getElementById('search-input');
             const searchTerm = searchInput.value.toLowerCase().trim();
             const searchResults = document.getElementById('search-results');
             const searchResultsList = document.getElementById('search-results-list');
-            const navigationContainer = document.getElementById('navigation-container');
+            const words = tokenize(searchTerm);
+            
+            // Keep the query in the URL so the search can be shared
+            const url = new URL(window.location.href);
+            if (words.length > 0) {
+                url.searchParams.set('q', searchInput.value.trim());
+            } else {
+                url.searchParams.delete('q');
+            }
+            history.replaceState(history.state, '', url);
             
-            if (searchTerm === '') {
-                // Show navigation, hide search results
+            selectSearchResult(-1);
+            if (words.length === 0) {
                 searchResults.style.display = 'none';
-                navigationContainer.style.display = 'block';
+                searchInput.setAttribute('aria-expanded', 'false');
                 return;
             }
             
-            // Hide navigation, show search results
-            navigationContainer.style.display = 'none';
             searchResults.style.display = 'block';
+            searchInput.setAttribute('aria-expanded', 'true');
+            
+            let manifest, records;
+            try {
+                manifest = await loadSearchManifest();
+                const results = await searchRecords(manifest, words);
+                records = await Promise.all(results.map(recordIndex => searchRecord(manifest, recordIndex)));
+            } catch (error) {
+                console.error('Search index failed to load:', error);
+                if (generation === searchGeneration) {
+                    showSearchMessage(searchResultsList, 'Search is unavailable right now');
+                }
+                return;
+            }
+            if (generation !== searchGeneration) return;
             
-            // Search through the index
-            const results = [];
+            // Display results
+            if (records.length === 0) {
+                showSearchMessage(searchResultsList, 'No results found');
+                return;
+            }
             
-            for (const [pageId, pageData] of Object.entries(searchIndex)) {
-                const titleMatch = pageData.title.toLowerCase().includes(searchTerm);
-                const sectionMatch = pageData.section.toLowerCase().includes(searchTerm);
-                const contentMatch = pageData.content.toLowerCase().includes(searchTerm);
+            searchResultsList.replaceChildren();
+            records.forEach((record, index) => {
+                const page = manifest.pages[record.page];
                 
-                if (titleMatch || sectionMatch || contentMatch) {
-                    // Find context around the match in content
-                    let snippet = '';
-                    if (contentMatch) {
-                        const contentLower = pageData.content.toLowerCase();
-                        const matchIndex = contentLower.indexOf(searchTerm);
-                        const start = Math.max(0, matchIndex - 60);
-                        const end = Math.min(pageData.content.length, matchIndex + searchTerm.length + 60);
-                        snippet = pageData.content.substring(start, end);
-                        
-                        // Add ellipsis if we're in the middle of the content
-                        if (start > 0) snippet = '...' + snippet;
-                        if (end < pageData.content.length) snippet = snippet + '...';
-                        
-                        // Highlight the search term
-                        const regex = new RegExp(`(${searchTerm})`, 'gi');
-                        snippet = snippet.replace(regex, '<mark>$1</mark>');
-                    }
-                    
-                    results.push({
-                        id: pageId,
-                        title: pageData.title,
-                        section: pageData.section,
-                        snippet: snippet,
-                        titleMatch: titleMatch,
-                        sectionMatch: sectionMatch,
-                        contentMatch: contentMatch
-                    });
+                const resultDiv = document.createElement('div');
+                resultDiv.className = 'search-result';
+                resultDiv.id = 'search-result-' + index;
+                resultDiv.setAttribute('role', 'option');
+                resultDiv.setAttribute('aria-selected', 'false');
+                resultDiv.onclick = () => showContentFromSearch(page.id, record.heading);
+                resultDiv.onmousemove = () => selectSearchResult(index);
+                
+                const titleDiv = document.createElement('div');
+                titleDiv.className = 'search-result-title';
+                titleDiv.textContent = record.heading_title ? page.title + ' › ' + record.heading_title : page.title;
+                resultDiv.appendChild(titleDiv);
+                
+                const sectionDiv = document.createElement('div');
+                sectionDiv.className = 'search-result-section';
+                sectionDiv.textContent = page.section;
+                resultDiv.appendChild(sectionDiv);
+                
+                // Page text is untrusted, so it only ever becomes text nodes
+                const snippet = record.text ? searchSnippet(record.text, words) : page.description;
+                if (snippet) {
+                    const snippetDiv = document.createElement('div');
+                    snippetDiv.className = 'search-result-snippet';
+                    appendHighlighted(snippetDiv, snippet, words);
+                    resultDiv.appendChild(snippetDiv);
                 }
-            }
-            
-            // Sort results by relevance (title matches first, then section matches, then content matches)
-            results.sort((a, b) => {
-                if (a.titleMatch && !b.titleMatch) return -1;
-                if (!a.titleMatch && b.titleMatch) return 1;
-                if (a.sectionMatch && !b.sectionMatch) return -1;
-                if (!a.sectionMatch && b.sectionMatch) return 1;
-                return 0;
+                
+                searchResultsList.appendChild(resultDiv);
+            });
+            selectSearchResult(0);
+        }
+        
+        // Index of the result Enter opens, or -1 when there is none
+        let selectedSearchResult = -1;
+        
+        function selectSearchResult(index) {
+            const results = document.querySelectorAll('#search-results-list .search-result');
+            const searchInput = document.getElementById('search-input');
+            results.forEach(result => {
+                result.classList.remove('selected');
+                result.setAttribute('aria-selected', 'false');
             });
             
-            // Display results
-            if (results.length === 0) {
-                searchResultsList.innerHTML = '<div class="no-results">No results found</div>';
-            } else {
-                let resultsHtml = '';
-                results.forEach(result => {
-                    const resultDiv = document.createElement('div');
-                    resultDiv.className = 'search-result';
-                    resultDiv.onclick = () => showContentFromSearch(result.id);
-                    
-                    const titleDiv = document.createElement('div');
-                    titleDiv.className = 'search-result-title';
-                    titleDiv.textContent = result.title;
-                    resultDiv.appendChild(titleDiv);
-                    
-                    const sectionDiv = document.createElement('div');
-                    sectionDiv.className = 'search-result-section';
-                    sectionDiv.textContent = result.section;
-                    resultDiv.appendChild(sectionDiv);
-                    
-                    if (result.snippet) {
-                        const snippetDiv = document.createElement('div');
-                        snippetDiv.className = 'search-result-snippet';
-                        snippetDiv.innerHTML = result.snippet;
-                        resultDiv.appendChild(snippetDiv);
-                    }
-                    
-                    resultsHtml += resultDiv.outerHTML;
-                });
-                
-                searchResultsList.innerHTML = resultsHtml;
+            selectedSearchResult = results.length > 0 ? Math.max(0, Math.min(index, results.length - 1)) : -1;
+            if (index < 0 || selectedSearchResult < 0) {
+                selectedSearchResult = -1;
+                searchInput.removeAttribute('aria-activedescendant');
+                return;
             }
+            
+            const selected = results[selectedSearchResult];
+            selected.classList.add('selected');
+            selected.setAttribute('aria-selected', 'true');
+            selected.scrollIntoView({ block: 'nearest' });
+            searchInput.setAttribute('aria-activedescendant', selected.id);
         }
         
-        function showContentFromSearch(contentId) {
-            // Clear search and show navigation
-            document.getElementById('search-input').value = '';
-            document.getElementById('search-results').style.display = 'none';
-            document.getElementById('navigation-container').style.display = 'block';
+        // Show the search palette, optionally searching for `query` right away
+        function openSearchPalette(query = null) {
+            const palette = document.getElementById('search-palette');
+            const searchInput = document.getElementById('search-input');
+            palette.hidden = false;
+            document.body.classList.add('search-open');
+            if (query !== null) {
+                searchInput.value = query;
+                performSearch();
+            }
+            searchInput.focus();
+            searchInput.select();
+        }
+        
+        function closeSearchPalette() {
+            const palette = document.getElementById('search-palette');
+            if (palette.hidden) return;
+            palette.hidden = true;
+            document.body.classList.remove('search-open');
+            
+            const searchInput = document.getElementById('search-input');
+            searchInput.value = '';
+            performSearch();
+            searchInput.blur();
+        }
+        
+        function showContentFromSearch(contentId, headerId) {
+            closeSearchPalette();
             
-            // Show the content directly using the combined ID
-            showContent(contentId);
+            // Show the content directly using the combined ID, scrolled to the matching heading
+            showContent(contentId, true, headerId || null);
         }
+        
+        // `/` or Ctrl-K opens the palette; inside it the arrow keys pick a result and Enter opens it
+        document.addEventListener('keydown', function(event) {
+            const palette = document.getElementById('search-palette');
+            
+            if (palette.hidden) {
+                const typing = event.target.closest('input, textarea, select, [contenteditable]');
+                if ((event.key === '/' && !typing && !event.ctrlKey && !event.metaKey && !event.altKey) ||
+                    (event.key.toLowerCase() === 'k' && (event.ctrlKey || event.metaKey))) {
+                    event.preventDefault();
+                    openSearchPalette();
+                }
+                return;
+            }
+            
+            switch (event.key) {
+                case 'Escape':
+                    event.preventDefault();
+                    closeSearchPalette();
+                    break;
+                case 'ArrowDown':
+                    event.preventDefault();
+                    selectSearchResult(selectedSearchResult + 1);
+                    break;
+                case 'ArrowUp':
+                    event.preventDefault();
+                    selectSearchResult(Math.max(0, selectedSearchResult - 1));
+                    break;
+                case 'Enter':
+                    if (selectedSearchResult >= 0) {
+                        event.preventDefault();
+                        document.querySelectorAll('#search-results-list .search-result')[selectedSearchResult].click();
+                    }
+                    break;
+                case 'k':
+                case 'K':
+                    if (event.ctrlKey || event.metaKey) {
+                        event.preventDefault();
+                        closeSearchPalette();
+                    }
+                    break;
+            }
+        });
 
         // Handle browser back/forward navigation
         window.addEventListener('popstate', function(event) {
-            if (event.state && event.state.contentId) {
+            if (multiPage) {
+                loadFromUrl();
+            } else if (event.state && event.state.contentId) {
                 // Show content without updating URL (to avoid infinite loop)
                 showContent(event.state.contentId, false);
             } else if (event.state && event.state.page === 'homepage') {
@@ -492,6 +780,26 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
 
         // Load content based on URL on page load
         function loadFromUrl() {
+            // A shared `?q=` search opens on top of the page
+            const query = new URLSearchParams(window.location.search).get('q');
+            if (query) {
+                openSearchPalette(query);
+            } else {
+                closeSearchPalette();
+            }
+            
+            // Multi-page documents already hold their page; the hash only names a header
+            if (multiPage) {
+                const activeContent = document.querySelector('.content-section.active');
+                if (activeContent) {
+                    const headerId = decodeURIComponent(window.location.hash.substring(1));
+                    showContent(activeContent.id, false, headerId || null);
+                } else {
+                    clearTableOfContents();
+                }
+                return;
+            }
+            
             const hash = window.location.hash.substring(1); // Remove the # symbol
             
             if (hash) {
@@ -526,7 +834,8 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
         document.addEventListener('click', function(event) {
             const navLink = event.target.closest('.nav-link[data-content-id]');
             
-            if (navLink) {
+            // Multi-page nav links are real links to the page files
+            if (navLink && !multiPage) {
                 event.preventDefault();
                 const contentId = navLink.getAttribute('data-content-id');
                 showContent(contentId);
@@ -548,6 +857,11 @@ pub fn generate_javascript(enable_hot_reload: bool) -> String {
         // Load content on initial page load
         document.addEventListener('DOMContentLoaded', function() {
             loadFromUrl();
+            
+            // Start downloading the search index as soon as the search box is used
+            document.getElementById('search-input').addEventListener('focus', () => {
+                loadSearchManifest().catch(() => {});
+            }, { once: true });
         });
 
         // Close sidebar when clicking outside on mobile
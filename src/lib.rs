@@ -0,0 +1,2250 @@
+//! GlowDoc builds documentation sites from a folder of markdown files and a `config.yaml`.
+//!
+//! [`GlowDocBuilder`] renders the site in memory with [`GlowDocBuilder::render`], or writes it
+//! to the output directory with [`GlowDocBuilder::build`]:
+//!
+//! ```no_run
+//! use glowdoc::{GlowDocBuilder, OutputMode};
+//!
+//! let site = GlowDocBuilder::new("docs")
+//!     .with_output_mode(OutputMode::MultiPage)
+//!     .render()?;
+//! for page in &site.pages {
+//!     println!("{}: {} bytes", page.path, page.html.len());
+//! }
+//! # Ok::<(), glowdoc::Error>(())
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use pulldown_cmark::{html, CowStr, Event, Tag};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+use futures_util::{SinkExt, StreamExt};
+use notify::{Watcher, RecursiveMode, recommended_watcher};
+use std::sync::mpsc;
+use std::thread;
+use tokio::sync::broadcast;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use hyper::{Body, Request, Response, Server};
+use hyper::service::{make_service_fn, service_fn};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+mod admonition;
+mod assets;
+mod cache;
+use assets::Assets;
+use cache::BuildCache;
+use config_builder::ConfigBuilder;
+
+pub mod config_builder;
+
+mod css;
+mod error;
+mod frontmatter;
+mod highlight;
+mod ignore;
+mod include;
+mod javascript;
+mod link_checker;
+mod navigation;
+mod page;
+mod preprocess;
+mod routes;
+mod search;
+mod validate;
+mod verbosity;
+use frontmatter::FrontMatter;
+use ignore::Ignore;
+use link_checker::LinkTargets;
+use page::ParsedPage;
+use rayon::prelude::*;
+use routes::{PageRoutes, Route};
+use search::{SearchPage, SearchRecord};
+
+pub use error::{Error, Result};
+pub use link_checker::BrokenLink;
+pub use navigation::Navigation;
+pub use preprocess::Preprocessor;
+pub use search::SearchIndex;
+pub use validate::{Diagnostic, Severity};
+pub use verbosity::{is_quiet, is_verbose, set_verbosity, Verbosity};
+
+/// A page, or a folder of pages, in the navigation
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NavigationItem {
+    /// May be omitted for pages, which then use their front matter title or first H1
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    pub id: String,
+    /// Markdown file relative to the docs folder, or `None` for a folder
+    pub file: Option<String>,
+    #[serde(default)]
+    pub items: Vec<NavigationItem>,
+    /// Headings of the page, filled in at build time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<HeaderItem>>,
+    /// Metadata from the page's front matter, filled in at build time
+    #[serde(skip)]
+    pub(crate) front_matter: FrontMatter,
+    #[serde(flatten)]
+    pub extra: UnknownKeys,
+}
+
+/// A heading shown under its page in the sidebar
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HeaderItem {
+    pub title: String,
+    pub id: String,
+    pub level: u8,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NavigationSection {
+    pub title: String,
+    pub id: String,
+    /// May be omitted when the navigation is derived, which then fills in the section's pages
+    #[serde(default)]
+    pub items: Vec<NavigationItem>,
+    #[serde(flatten)]
+    pub extra: UnknownKeys,
+}
+
+/// The contents of `config.yaml`, shared by the build and `init-config`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Config {
+    pub title: String,
+    pub description: String,
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// Sections of the sidebar; `auto`, or no `navigation` key, derives them from the docs folder
+    #[serde(default = "Navigation::derived")]
+    pub navigation: Navigation,
+    #[serde(default)]
+    pub social: SocialLinks,
+    #[serde(default, skip_serializing_if = "OutputMode::is_default")]
+    pub output_mode: OutputMode,
+    /// Docs root, used when `--root` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Output directory, used when `--out` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub out: Option<String>,
+    /// External commands that rewrite the page markdown before rendering, run in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preprocessors: Vec<Preprocessor>,
+    /// Globs of files and folders in the docs folder to leave out, in addition to those in
+    /// `.glowdocignore`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    #[serde(flatten)]
+    pub extra: UnknownKeys,
+}
+
+/// Keys of `config.yaml` this version of GlowDoc does not know, kept so that rewriting the
+/// config (e.g. with `init-config`) leaves settings for newer versions and other tools intact
+pub type UnknownKeys = BTreeMap<String, serde_yaml::Value>;
+
+impl Config {
+    /// Read and parse a config file. Syntax and type errors name the file, line and column,
+    /// e.g. `docs/config.yaml:9:13: navigation[0].items[0].id: invalid type`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let config_content = fs::read_to_string(path).map_err(|e| Error::config(path, e.to_string()))?;
+        serde_yaml::from_str(&config_content).map_err(|e| {
+            let message = e.to_string();
+            let position = e.location().map(|location| (location.line(), location.column()));
+            let message = match position {
+                Some((line, column)) => {
+                    let suffix = format!(" at line {} column {}", line, column);
+                    message.strip_suffix(&suffix).unwrap_or(&message).to_string()
+                }
+                None => message,
+            };
+            Error::Config {
+                path: path.to_path_buf(),
+                problems: vec![Diagnostic { severity: Severity::Error, position, message }],
+            }
+        })
+    }
+}
+
+/// The path keys of `config.yaml`, read before the builder knows where everything lives
+#[derive(Debug, Deserialize, Default)]
+struct ConfigPaths {
+    #[serde(default)]
+    root: Option<String>,
+    #[serde(default)]
+    out: Option<String>,
+}
+
+/// How the generated site is laid out on disk
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputMode {
+    /// Every page in one `index.html`, switched client-side through hash routes
+    #[default]
+    SingleFile,
+    /// One `index.html` per navigation page, e.g. `introduction/what-is-glowdoc/index.html`
+    MultiPage,
+}
+
+impl OutputMode {
+    fn is_default(&self) -> bool {
+        *self == OutputMode::default()
+    }
+}
+
+/// The page currently being rendered, used to build links that work from its location
+struct PageContext {
+    mode: OutputMode,
+    /// Combined ID of the page, or `None` for the homepage
+    current_id: Option<String>,
+    /// Title of the page, or `None` for the homepage
+    title: Option<String>,
+    /// Front matter description, replacing the site description in the page's meta tag
+    description: Option<String>,
+    /// Relative path from the page back to the site root, e.g. `../../`
+    root_prefix: String,
+}
+
+impl PageContext {
+    fn single_file() -> Self {
+        PageContext {
+            mode: OutputMode::SingleFile,
+            current_id: None,
+            title: None,
+            description: None,
+            root_prefix: String::new(),
+        }
+    }
+
+    fn homepage(mode: OutputMode) -> Self {
+        PageContext {
+            mode,
+            ..Self::single_file()
+        }
+    }
+
+    /// Where the body of a page ends up, used to rewrite its links while it is rendered
+    fn page_body(mode: OutputMode, combined_id: &str) -> Self {
+        match mode {
+            OutputMode::SingleFile => Self::single_file(),
+            OutputMode::MultiPage => PageContext {
+                mode,
+                current_id: Some(combined_id.to_string()),
+                title: None,
+                description: None,
+                root_prefix: "../".repeat(combined_id.split('/').count()),
+            },
+        }
+    }
+
+    fn page(page: &RenderedPage) -> Self {
+        PageContext {
+            title: Some(page.title.clone()),
+            description: page.description.clone(),
+            ..Self::page_body(OutputMode::MultiPage, &page.id)
+        }
+    }
+
+    /// Link to a page, and a heading on it when the route has a fragment
+    fn route_href(&self, route: &Route) -> String {
+        let href = self.page_href(&route.page_id);
+        match &route.fragment {
+            Some(fragment) => format!("{}#{}", href, fragment),
+            None => href,
+        }
+    }
+
+    /// Link to a page by its combined ID
+    fn page_href(&self, combined_id: &str) -> String {
+        match self.mode {
+            OutputMode::SingleFile => format!("#{}", combined_id),
+            OutputMode::MultiPage => format!("{}{}/index.html", self.root_prefix, combined_id),
+        }
+    }
+}
+
+/// Shared state while rendering markdown: routes for links between pages, the output mode,
+/// the files the pages reference and the pages rendered by previous builds
+struct RenderContext<'a> {
+    routes: &'a PageRoutes,
+    mode: OutputMode,
+    assets: &'a Assets,
+    cache: &'a BuildCache,
+    /// Links that could not be rewritten, including those of pages reused from the cache
+    warnings: &'a Mutex<Vec<String>>,
+}
+
+impl RenderContext<'_> {
+    /// Cache context for a page rendered at `combined_id` (`None` for the homepage)
+    fn context_hash(&self, combined_id: Option<&str>) -> String {
+        let mode = format!("{:?}", self.mode);
+        cache::context_hash(&[&mode, combined_id.unwrap_or(""), &self.routes.fingerprint()])
+    }
+}
+
+/// A navigation page rendered to HTML, before it is placed into a document
+struct RenderedPage {
+    /// Combined ID, e.g. `getting-started/setup/setup-installation`
+    id: String,
+    title: String,
+    description: Option<String>,
+    /// Layout from the front matter, see [`frontmatter::LAYOUTS`]
+    layout: String,
+    html: String,
+}
+
+/// A rendered site, as [`GlowDocBuilder::build`] writes it
+pub struct Site {
+    /// The config the site was built from, with page titles and headings filled in
+    pub config: Config,
+    /// HTML documents, the homepage (`index.html`) first. Multi-page sites add one per page,
+    /// e.g. `introduction/what-is-glowdoc/index.html`.
+    pub pages: Vec<SitePage>,
+    /// Files the pages reference, copied under `assets/`
+    pub assets: Vec<SiteFile>,
+    pub search_index: SearchIndex,
+    /// The compressed shards multi-page sites load the search index from; single-file sites
+    /// keep the index inline
+    pub search_files: Vec<SiteFile>,
+    /// Problems that did not stop the build, such as config warnings and links that could not
+    /// be rewritten. A strict build fails on them instead.
+    pub warnings: Vec<String>,
+}
+
+pub struct SitePage {
+    /// Path relative to the output directory
+    pub path: String,
+    pub html: String,
+}
+
+/// A file written next to the pages
+pub struct SiteFile {
+    /// Path relative to the output directory, e.g. `assets/logo-3f9a2c1e.png`
+    pub path: String,
+    pub content: Vec<u8>,
+}
+
+/// Accounts linked from the header
+#[derive(Debug, Deserialize, Serialize, Default, Clone)]
+pub struct SocialLinks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instagram: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mastodon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threads: Option<String>,
+}
+
+fn default_theme() -> String {
+    "default".to_string()
+}
+
+/// Paths given on the command line with `--root`, `--config` and `--out`
+#[derive(Debug, Clone, Default)]
+pub struct PathOptions {
+    pub root: Option<String>,
+    pub config: Option<String>,
+    pub out: Option<String>,
+}
+
+impl PathOptions {
+    /// Config file location: `--config`, or `config.yaml` inside `--root` or `docs`
+    pub fn config_path(&self) -> String {
+        self.config.clone().unwrap_or_else(|| {
+            Path::new(self.root.as_deref().unwrap_or("docs")).join("config.yaml").display().to_string()
+        })
+    }
+}
+
+/// Builds a site from a docs folder. Paths are relative to the working directory.
+#[derive(Clone)]
+pub struct GlowDocBuilder {
+    docs_path: String,
+    config_path: String,
+    entry_path: String,
+    output_dir: String,
+    output_path: String,
+    /// Replaces the `output_mode` of the config when set
+    output_mode: Option<OutputMode>,
+    /// Fail on warnings such as missing page files and links that cannot be resolved
+    strict: bool,
+    /// Files outside the navigation that the last build included, shared with clones so
+    /// `watch` can follow them
+    dependencies: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl GlowDocBuilder {
+    /// A builder for the docs in `docs_path`, configured by its `config.yaml` and writing to
+    /// the working directory
+    pub fn new(docs_path: &str) -> Self {
+        let config_path = Path::new(docs_path).join("config.yaml").display().to_string();
+        Self::with_paths(docs_path, &config_path, ".")
+    }
+
+    fn with_paths(docs_path: &str, config_path: &str, output_dir: &str) -> Self {
+        GlowDocBuilder {
+            docs_path: docs_path.to_string(),
+            config_path: config_path.to_string(),
+            entry_path: Path::new(docs_path).join("entry.md").display().to_string(),
+            output_dir: output_dir.to_string(),
+            output_path: Self::output_path_in(output_dir),
+            output_mode: None,
+            strict: false,
+            dependencies: Arc::default(),
+        }
+    }
+
+    /// Resolve the build paths: command-line options win, then the `root` and `out` keys of
+    /// the config file, then the defaults
+    pub fn from_options(options: &PathOptions) -> Self {
+        let config_path = options.config_path();
+        
+        // A missing or malformed config is reported when the build loads it
+        let config_paths = fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_yaml::from_str::<ConfigPaths>(&content).ok())
+            .unwrap_or_default();
+        
+        let docs_path = options.root.clone().or(config_paths.root).unwrap_or_else(|| "docs".to_string());
+        let output_dir = options.out.clone().or(config_paths.out).unwrap_or_else(|| ".".to_string());
+        
+        Self::with_paths(&docs_path, &config_path, &output_dir)
+    }
+
+    /// The homepage written into `output_dir`
+    fn output_path_in(output_dir: &str) -> String {
+        // Keep the historical `index.html` (rather than `./index.html`) for the default output
+        if Path::new(output_dir) == Path::new(".") {
+            "index.html".to_string()
+        } else {
+            Path::new(output_dir).join("index.html").display().to_string()
+        }
+    }
+
+    /// Read the config from `config_path` instead of `config.yaml` in the docs folder
+    pub fn with_config_path(mut self, config_path: &str) -> Self {
+        self.config_path = config_path.to_string();
+        self
+    }
+
+    /// Write the site to `output_dir` instead of the working directory
+    pub fn with_output_dir(mut self, output_dir: &str) -> Self {
+        self.output_path = Self::output_path_in(output_dir);
+        self.output_dir = output_dir.to_string();
+        self
+    }
+
+    /// Build in `output_mode` whatever the config says
+    pub fn with_output_mode(mut self, output_mode: OutputMode) -> Self {
+        self.output_mode = Some(output_mode);
+        self
+    }
+
+    /// Fail the build on warnings: problems in the config such as missing page files and
+    /// unknown themes, links that cannot be resolved and page files that cannot be read
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn docs_path(&self) -> &str {
+        &self.docs_path
+    }
+
+    pub fn config_path(&self) -> &str {
+        &self.config_path
+    }
+
+    pub fn output_dir(&self) -> &str {
+        &self.output_dir
+    }
+
+    fn get_current_year(&self) -> i32 {
+        let now = SystemTime::now();
+        let duration_since_epoch = now.duration_since(UNIX_EPOCH).unwrap();
+        let seconds = duration_since_epoch.as_secs();
+        
+        // Convert seconds to years (approximate)
+        // 1970 + (seconds / seconds_per_year)
+        // Using 365.25 days per year to account for leap years
+        let seconds_per_year = 365.25 * 24.0 * 60.0 * 60.0;
+        let years_since_1970 = (seconds as f64) / seconds_per_year;
+        1970 + years_since_1970 as i32
+    }
+
+    /// Load the config the site is built from, with the sections of `navigation: auto`
+    /// derived from the docs folder
+    pub fn load_config(&self) -> Result<Config> {
+        self.derive_navigation(self.read_config()?, &mut Vec::new())
+    }
+
+    /// The config as written, which is what [`Self::validate_config`] checks
+    fn read_config(&self) -> Result<Config> {
+        let mut config = Config::load(&self.config_path)?;
+        if let Some(output_mode) = self.output_mode {
+            config.output_mode = output_mode;
+        }
+        Ok(config)
+    }
+
+    /// Fill in the navigation of a config that derives it, at the position of its `auto`
+    /// entry. Sections and folder items the config lists keep their title, ID and order, and
+    /// the pages they leave out are added to them. Pages that cannot be read are left out and
+    /// added to `warnings`, or fail a strict build.
+    fn derive_navigation(&self, mut config: Config, warnings: &mut Vec<String>) -> Result<Config> {
+        let Some(position) = config.navigation.auto.take() else {
+            return Ok(config);
+        };
+        let builder = ConfigBuilder::new(&self.docs_path).with_exclude(config.exclude.clone());
+        let mut detected = builder.detect_structure()?;
+        for (path, message) in std::mem::take(&mut detected.skipped) {
+            if self.strict {
+                return Err(Error::Markdown { path, message });
+            }
+            warnings.push(format!("{}: {}, so it is not built", path.display(), message));
+        }
+        builder.add_detected_pages(&mut config.navigation, detected, position);
+        Ok(config)
+    }
+
+    /// Files of the docs folder that `.glowdocignore` and the `exclude:` globs of `config`
+    /// leave out
+    fn ignore(&self, config: &Config) -> Result<Ignore> {
+        Ignore::load(&self.docs_path, &config.exclude)
+    }
+
+    /// [`Self::ignore`] for the watcher, which keeps running when the config cannot be read
+    fn watch_ignore(&self) -> Ignore {
+        self.read_config().and_then(|config| self.ignore(&config)).unwrap_or_default()
+    }
+
+    /// Page files of the docs folder that the navigation of `config` does not list, and that
+    /// are not ignored. A derived navigation lists every page.
+    fn orphans(&self, config: &Config) -> Result<Vec<String>> {
+        if config.navigation.auto.is_some() {
+            return Ok(Vec::new());
+        }
+        let builder = ConfigBuilder::new(&self.docs_path).with_exclude(config.exclude.clone());
+        let detected = builder.detect_structure()?;
+        Ok(builder.add_detected_pages(&mut config.navigation.sections.clone(), detected, 0))
+    }
+
+    /// Problems with a loaded config that its syntax does not reveal, such as missing page
+    /// files and duplicate IDs, each pointing at its line in the config file
+    pub fn validate_config(&self, config: &Config) -> Vec<Diagnostic> {
+        let source = fs::read_to_string(&self.config_path).unwrap_or_default();
+        validate::validate(config, &source, Path::new(&self.docs_path))
+    }
+
+    /// Fail when [`Self::validate_config`] finds errors, returning its warnings otherwise. A
+    /// strict build fails on warnings too.
+    fn require_valid_config(&self, config: &Config) -> Result<Vec<String>> {
+        let (errors, warnings): (Vec<Diagnostic>, Vec<Diagnostic>) = self.validate_config(config)
+            .into_iter()
+            .partition(|diagnostic| self.strict || diagnostic.severity == Severity::Error);
+        
+        if errors.is_empty() {
+            return Ok(warnings.iter().map(|warning| warning.display(&self.config_path).to_string()).collect());
+        }
+        Err(Error::Config { path: PathBuf::from(&self.config_path), problems: errors })
+    }
+
+    /// Remember the files `page` includes for `watch`
+    fn record_dependencies(&self, page: &ParsedPage) {
+        self.dependencies.lock().unwrap().extend(page.dependencies.iter().cloned());
+    }
+    
+    /// Key of a markdown file in the build cache, unique across docs folders
+    fn cache_key(&self, file_path: &str) -> String {
+        Path::new(&self.docs_path).join(file_path).display().to_string()
+    }
+
+    /// Render a parsed markdown file with IDs on its headings, rewriting its links to other
+    /// pages and to copied assets, and highlighting its code blocks. Returns the HTML with
+    /// the links that could not be rewritten.
+    fn markdown_to_html(&self, source: &ParsedPage, render: &RenderContext, page: &PageContext) -> (String, Vec<String>) {
+        let file_path = source.file.as_str();
+        let mut warnings = Vec::new();
+        let events = source.render_events().map(|event| match event {
+            Event::Start(Tag::Link(link_type, destination, title)) => {
+                let destination = self.rewrite_link(file_path, &destination, render.routes, page, &mut warnings)
+                    .or_else(|| self.asset_url(file_path, &destination, render.assets, page))
+                    .map_or(destination, CowStr::from);
+                Event::Start(Tag::Link(link_type, destination, title))
+            }
+            Event::Start(Tag::Image(link_type, destination, title)) => {
+                let destination = self.asset_url(file_path, &destination, render.assets, page)
+                    .map_or(destination, CowStr::from);
+                Event::Start(Tag::Image(link_type, destination, title))
+            }
+            Event::Html(html) => Event::Html(self.rewrite_html_assets(file_path, &html, render.assets, page).into()),
+            event => event,
+        });
+        
+        let mut html_output = String::new();
+        html::push_html(&mut html_output, highlight::highlight_code_blocks(events).into_iter());
+        (html_output, warnings)
+    }
+
+    /// Route for a link to another page: relative `.md` links, and in multi-page output the
+    /// `#section/page` hash routes, which only work inside the single-file document. Links
+    /// that cannot be resolved are added to `warnings`.
+    fn rewrite_link(&self, file_path: &str, destination: &str, routes: &PageRoutes, page: &PageContext, warnings: &mut Vec<String>) -> Option<String> {
+        match routes.resolve(file_path, destination) {
+            Some(Ok(route)) => Some(page.route_href(&route)),
+            Some(Err(reason)) => {
+                warnings.push(format!("{}: link '{}' was not rewritten: {}", file_path, destination, reason));
+                None
+            }
+            None => Route::from_hash(destination)
+                .filter(|_| page.mode == OutputMode::MultiPage)
+                .map(|route| page.route_href(&route)),
+        }
+    }
+
+    /// URL of the copied asset for a reference to a local file, relative to the page
+    fn asset_url(&self, file_path: &str, url: &str, assets: &Assets, page: &PageContext) -> Option<String> {
+        assets.register(file_path, url).map(|path| format!("{}{}", page.root_prefix, path))
+    }
+
+    /// Rewrite `src`, `poster` and `href` attributes of raw HTML such as `<img>` and `<video>`
+    fn rewrite_html_assets(&self, file_path: &str, html: &str, assets: &Assets, page: &PageContext) -> String {
+        use regex::Regex;
+        use std::sync::OnceLock;
+        
+        static ASSET_ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+        let re = ASSET_ATTRIBUTE.get_or_init(|| Regex::new(r#"\b(src|poster|href)=(["'])([^"']*)(["'])"#).unwrap());
+        
+        re.replace_all(html, |caps: &regex::Captures| {
+            match self.asset_url(file_path, &caps[3], assets, page) {
+                Some(url) => format!("{}={}{}{}", &caps[1], &caps[2], url, &caps[4]),
+                None => caps[0].to_string(),
+            }
+        }).to_string()
+    }
+
+    /// Page files in the navigation, for resolving links between them
+    fn page_routes(&self, navigation: &[NavigationSection]) -> PageRoutes {
+        fn add_items(items: &[NavigationItem], path_prefix: &str, routes: &mut PageRoutes) {
+            for item in items {
+                let combined_id = format!("{}/{}", path_prefix, item.id);
+                if let Some(file) = &item.file {
+                    routes.add(file, &combined_id);
+                }
+                add_items(&item.items, &combined_id, routes);
+            }
+        }
+        
+        let mut routes = PageRoutes::default();
+        for section in navigation {
+            add_items(&section.items, &section.id, &mut routes);
+        }
+        routes
+    }
+
+    fn generate_sidebar(&self, navigation: &[NavigationSection], page: &PageContext) -> String {
+        let mut sidebar_html = String::new();
+        
+        // The search box at the top of the sidebar opens the search palette
+        sidebar_html.push_str(r#"
+                <div class="search-container">
+                    <button type="button" class="search-trigger" onclick="openSearchPalette()" aria-keyshortcuts="/ Control+K">
+                        <span>Search pages...</span>
+                        <kbd>/</kbd>
+                    </button>
+                    <svg class="search-icon" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                        <circle cx="11" cy="11" r="8"/>
+                        <path d="m21 21-4.35-4.35"/>
+                    </svg>
+                </div>
+                <div id="navigation-container">"#);
+        
+        for section in navigation {
+            sidebar_html.push_str(&format!(
+                "\n                <div class=\"nav-section\">\n                    <div class=\"nav-section-title\" onclick=\"toggleSection('{}')\">\n                        <span>{}</span>\n                        <svg class=\"nav-section-toggle\" width=\"12\" height=\"12\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">\n                            <polyline points=\"6 9 12 15 18 9\"></polyline>\n                        </svg>\n                    </div>\n                    <ul class=\"nav-items\" id=\"{}-items\">",
+                section.id, section.title, section.id
+            ));
+            
+            for (index, item) in section.items.iter().enumerate() {
+                // In single-file mode the first page starts out active; every other page is
+                // marked active only on its own file
+                let is_first = page.mode == OutputMode::SingleFile && navigation.first().map(|s| s.id == section.id).unwrap_or(false) && index == 0 && item.file.is_some();
+                let active_class = if is_first { " active" } else { "" };
+                
+                self.generate_nav_item(&mut sidebar_html, item, section, 0, active_class, page);
+            }
+            
+            sidebar_html.push_str("\n                    </ul>\n                </div>");
+        }
+        
+        sidebar_html.push_str("\n                </div>"); // Close navigation-container
+        
+        sidebar_html
+    }
+
+    fn generate_nav_item(&self, html: &mut String, item: &NavigationItem, section: &NavigationSection, depth: usize, active_class: &str, page: &PageContext) {
+        self.generate_nav_item_with_path(html, item, section, depth, active_class, &section.id, page)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_nav_item_with_path(&self, html: &mut String, item: &NavigationItem, section: &NavigationSection, depth: usize, active_class: &str, path_prefix: &str, page: &PageContext) {
+        let indent = "    ".repeat(depth + 6); // Base indentation + depth
+        
+        if item.file.is_some() {
+            // This is a page item - use combined path for URL and content ID
+            let combined_id = format!("{}/{}", path_prefix, item.id);
+            let active_class = if page.current_id.as_deref() == Some(combined_id.as_str()) { " active" } else { active_class };
+            html.push_str(&format!(
+                "\n{}<li class=\"nav-item\">\n{}    <a href=\"{}\" class=\"nav-link{}\" data-content-id=\"{}\" data-section-id=\"{}\">{}</a>\n{}</li>",
+                indent, indent, page.page_href(&combined_id), active_class, combined_id, section.id, item.title, indent
+            ));
+        } else if !item.items.is_empty() {
+            // This is a nested folder
+            let folder_path = format!("{}/{}", path_prefix, item.id);
+            html.push_str(&format!(
+                "\n{}<li class=\"nav-item nav-folder\">\n{}    <div class=\"nav-folder-title\" onclick=\"toggleNestedSection('{}')\">\n{}        <span>{}</span>\n{}        <svg class=\"nav-folder-toggle\" width=\"10\" height=\"10\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">\n{}            <polyline points=\"6 9 12 15 18 9\"></polyline>\n{}        </svg>\n{}    </div>\n{}    <ul class=\"nav-nested-items\" id=\"{}-items\">",
+                indent, indent, folder_path.replace("/", "-"), indent, item.title, indent, indent, indent, indent, indent, folder_path.replace("/", "-")
+            ));
+            
+            for nested_item in &item.items {
+                self.generate_nav_item_with_path(html, nested_item, section, depth + 1, "", &folder_path, page);
+            }
+            
+            html.push_str(&format!("\n{}    </ul>\n{}</li>", indent, indent));
+        }
+    }
+
+    fn generate_content(&self, navigation: &[NavigationSection], sources: &HashMap<String, ParsedPage>, render: &RenderContext) -> Result<(Vec<RenderedPage>, SearchIndex)> {
+        // Pages only share the routes and asset registry, so they render in parallel
+        let rendered: Vec<(RenderedPage, Option<SearchPage>)> = self.content_items(navigation)
+            .par_iter()
+            .map(|(combined_id, item, section)| self.render_content_item(combined_id, item, section, sources, render))
+            .collect();
+        
+        let (pages, search_pages): (Vec<RenderedPage>, Vec<Option<SearchPage>>) = rendered.into_iter().unzip();
+        let search_index = SearchIndex::build(search_pages.into_iter().flatten().collect());
+        
+        Ok((pages, search_index))
+    }
+
+    /// Page items in navigation order with their combined IDs and sections
+    fn content_items<'a>(&self, navigation: &'a [NavigationSection]) -> Vec<(String, &'a NavigationItem, &'a NavigationSection)> {
+        fn collect<'a>(items: &'a [NavigationItem], section: &'a NavigationSection, path_prefix: &str, content_items: &mut Vec<(String, &'a NavigationItem, &'a NavigationSection)>) {
+            for item in items {
+                let combined_id = format!("{}/{}", path_prefix, item.id);
+                if item.file.is_some() {
+                    content_items.push((combined_id.clone(), item, section));
+                }
+                collect(&item.items, section, &combined_id, content_items);
+            }
+        }
+        
+        let mut content_items = Vec::new();
+        for section in navigation {
+            collect(&section.items, section, &section.id, &mut content_items);
+        }
+        content_items
+    }
+
+    /// Render one page, returning it with what the search index needs to know about it
+    fn render_content_item(&self, combined_id: &str, item: &NavigationItem, section: &NavigationSection, sources: &HashMap<String, ParsedPage>, render: &RenderContext) -> (RenderedPage, Option<SearchPage>) {
+        let file = item.file.as_deref().unwrap_or_default();
+        let search_page = |description: Option<String>, tags: Vec<String>, records: Vec<SearchRecord>| SearchPage {
+            id: combined_id.to_string(),
+            title: item.title.clone(),
+            section: section.title.clone(),
+            description,
+            tags,
+            records,
+        };
+        
+        let Some(source) = sources.get(file) else {
+            // Files that could not be read were reported when the pages were loaded
+            let page = RenderedPage {
+                id: combined_id.to_string(),
+                title: item.title.clone(),
+                description: None,
+                layout: "default".to_string(),
+                html: format!("<p>Error loading content: {}</p>", file),
+            };
+            
+            // Keep the page findable by its title
+            let record = SearchRecord { heading: None, heading_title: None, text: String::new() };
+            return (page, Some(search_page(None, Vec::new(), vec![record])));
+        };
+        
+        let front_matter = &source.front_matter;
+        let cache_key = self.cache_key(file);
+        let context_hash = render.context_hash(Some(combined_id));
+        
+        // Reuse the previous build's output unless the page or anything it links to changed
+        let cached = render.cache.rendered(&cache_key, &source.content_hash, &context_hash)
+            .filter(|cached| render.assets.restore(file, &cached.assets));
+        let rendered = match cached {
+            Some(cached) => cached,
+            None => {
+                let page = PageContext::page_body(render.mode, combined_id);
+                let (html, warnings) = self.markdown_to_html(source, render, &page);
+                let rendered = cache::CachedRender {
+                    context_hash,
+                    html,
+                    search_records: source.search_records(),
+                    assets: render.assets.references(file),
+                    warnings,
+                };
+                render.cache.store_rendered(&cache_key, &source.content_hash, rendered.clone());
+                rendered
+            }
+        };
+        render.warnings.lock().unwrap().extend(rendered.warnings);
+        
+        let page = RenderedPage {
+            id: combined_id.to_string(),
+            title: item.title.clone(),
+            description: front_matter.description.clone(),
+            layout: front_matter.layout().to_string(),
+            html: rendered.html,
+        };
+        let search_page = (!front_matter.hide_from_search)
+            .then(|| search_page(front_matter.description.clone(), front_matter.tags.clone(), rendered.search_records));
+        (page, search_page)
+    }
+
+    /// Combined ID of the first page in the navigation, which may be nested in folders
+    fn first_page_id(&self, navigation: &[NavigationSection]) -> Option<String> {
+        self.content_items(navigation)
+            .into_iter()
+            .next()
+            .map(|(combined_id, _, _)| combined_id)
+    }
+
+    /// Wrap a rendered page in the `<section>` element the router shows and hides
+    fn wrap_content_section(&self, page: &RenderedPage, active: bool) -> String {
+        let layout_class = if page.layout == "default" {
+            String::new()
+        } else {
+            format!(" page-layout-{}", page.layout)
+        };
+        
+        format!(
+            "\n            <section class=\"content-section{}{}\" id=\"{}\">\n                {}\n            </section>",
+            layout_class, if active { " active" } else { "" }, page.id, page.html
+        )
+    }
+
+    /// All pages in one document, as written by the single-file output mode
+    fn generate_single_file_content(&self, navigation: &[NavigationSection], pages: &[RenderedPage]) -> String {
+        let first_page_id = self.first_page_id(navigation);
+        pages.iter()
+            .map(|page| self.wrap_content_section(page, first_page_id.as_deref() == Some(page.id.as_str())))
+            .collect()
+    }
+
+    /// Raw markdown of entry.md, when it exists, and of every page file in the navigation,
+    /// keyed by path relative to the docs folder. Files that cannot be read are returned with
+    /// their errors.
+    fn read_book(&self, navigation: &[NavigationSection]) -> (BTreeMap<String, String>, BTreeMap<String, io::Error>) {
+        let mut book = BTreeMap::new();
+        let mut unreadable = BTreeMap::new();
+
+        let files = self.content_items(navigation).into_iter()
+            .map(|(_, item, _)| item.file.as_deref().unwrap_or_default());
+        let entry = Path::new(&self.entry_path).exists().then_some("entry.md");
+        for file in entry.into_iter().chain(files) {
+            if book.contains_key(file) || unreadable.contains_key(file) {
+                continue;
+            }
+            match fs::read_to_string(Path::new(&self.docs_path).join(file)) {
+                Ok(content) => {
+                    book.insert(file.to_string(), content);
+                }
+                Err(e) => {
+                    unreadable.insert(file.to_string(), e);
+                }
+            }
+        }
+
+        (book, unreadable)
+    }
+
+    /// Read every page file in the navigation and entry.md once, run the preprocessors over
+    /// them and parse the result. Page files that cannot be read are rendered as an error
+    /// message and added to `warnings`, unless the build is strict; invalid front matter or a
+    /// failing preprocessor fails the build.
+    fn load_pages(&self, config: &Config, warnings: &mut Vec<String>) -> Result<HashMap<String, ParsedPage>> {
+        let (mut book, unreadable) = self.read_book(&config.navigation);
+        for (file, e) in unreadable {
+            let path = Path::new(&self.docs_path).join(&file);
+            if file == "entry.md" || self.strict {
+                return Err(Error::Markdown { path, message: e.to_string() });
+            }
+            // Missing files were reported when the config was validated
+            if path.exists() {
+                warnings.push(format!("{}: {}", path.display(), e));
+            }
+        }
+
+        preprocess::run(config, config.output_mode, &self.docs_path, &mut book)?;
+
+        let mut sources = HashMap::new();
+        for (file, content) in book {
+            let page = ParsedPage::from_source(Path::new(&self.docs_path), &file, &content)?;
+            self.record_dependencies(&page);
+            sources.insert(file, page);
+        }
+        Ok(sources)
+    }
+
+    fn extract_headers_and_update_navigation(&self, navigation: &mut [NavigationSection], sources: &HashMap<String, ParsedPage>, cache: &BuildCache) {
+        for section in navigation.iter_mut() {
+            for item in section.items.iter_mut() {
+                self.update_item_headers(item, sources, cache);
+            }
+            Self::remove_drafts(&mut section.items);
+        }
+    }
+
+    /// Drop pages marked `draft: true` in their front matter, along with anything nested under them
+    fn remove_drafts(items: &mut Vec<NavigationItem>) {
+        items.retain(|item| !item.front_matter.draft);
+        for item in items.iter_mut() {
+            Self::remove_drafts(&mut item.items);
+        }
+    }
+
+    fn update_item_headers(&self, item: &mut NavigationItem, sources: &HashMap<String, ParsedPage>, cache: &BuildCache) {
+        if let Some(source) = item.file.as_ref().and_then(|file| sources.get(file)) {
+            let cache_key = self.cache_key(&source.file);
+            let headers = cache.headers(&cache_key, &source.content_hash).unwrap_or_else(|| {
+                let headers = source.headers();
+                cache.store_headers(&cache_key, &source.content_hash, &headers);
+                headers
+            });
+            
+            // Pages without a title in the config fall back to the front matter, then the first H1
+            if item.title.is_empty() {
+                item.title = source.front_matter.title.clone()
+                    .or_else(|| headers.iter().find(|header| header.level == 1).map(|header| header.title.clone()))
+                    .unwrap_or_else(|| item.id.clone());
+            }
+            
+            if !headers.is_empty() {
+                item.headers = Some(headers);
+            }
+            item.front_matter = source.front_matter.clone();
+        }
+        
+        // Recursively process nested items
+        for nested_item in item.items.iter_mut() {
+            self.update_item_headers(nested_item, sources, cache);
+        }
+    }
+
+    fn entry_not_found(&self) -> Error {
+        let message = format!(
+            "entry file not found\n\nThe entry.md file is required for the homepage content.\nPlease create this file in your {}/ folder with your homepage markdown content.",
+            self.docs_path
+        );
+        Error::Markdown { path: PathBuf::from(&self.entry_path), message }
+    }
+
+    /// Render entry.md from the pages returned by [`Self::load_pages`]
+    fn load_homepage(&self, sources: &HashMap<String, ParsedPage>, render: &RenderContext, page: &PageContext) -> Result<String> {
+        let source = sources.get("entry.md").ok_or_else(|| self.entry_not_found())?;
+        let cache_key = self.cache_key("entry.md");
+        let context_hash = render.context_hash(page.current_id.as_deref());
+        
+        if let Some(cached) = render.cache.rendered(&cache_key, &source.content_hash, &context_hash) {
+            if render.assets.restore("entry.md", &cached.assets) {
+                render.warnings.lock().unwrap().extend(cached.warnings);
+                return Ok(cached.html);
+            }
+        }
+        
+        let (html, warnings) = self.markdown_to_html(source, render, page);
+        render.warnings.lock().unwrap().extend(warnings.iter().cloned());
+        render.cache.store_rendered(&cache_key, &source.content_hash, cache::CachedRender {
+            context_hash,
+            html: html.clone(),
+            search_records: Vec::new(),
+            assets: render.assets.references("entry.md"),
+            warnings,
+        });
+        
+        Ok(html)
+    }
+
+
+
+    fn generate_social_links_html(&self, social: &SocialLinks) -> String {
+        let mut social_html = String::new();
+        
+        if social.instagram.is_some() || social.github.is_some() || social.mastodon.is_some() || social.threads.is_some() {
+            social_html.push_str(r#"<div class="social-links">"#);
+            
+            if let Some(instagram) = &social.instagram {
+                social_html.push_str(&format!(r#"
+                    <a href="https://instagram.com/{}" class="social-link" target="_blank" rel="noopener noreferrer" aria-label="Instagram">
+                        <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <rect x="2" y="2" width="20" height="20" rx="5" ry="5"/>
+                            <path d="m16 11.37A4 4 0 1 1 12.63 8 4 4 0 0 1 16 11.37z"/>
+                            <line x1="17.5" y1="6.5" x2="17.51" y2="6.5"/>
+                        </svg>
+                    </a>"#, instagram));
+            }
+            
+            if let Some(github) = &social.github {
+                social_html.push_str(&format!(r#"
+                    <a href="https://github.com/{}" class="social-link" target="_blank" rel="noopener noreferrer" aria-label="GitHub">
+                        <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+                            <path d="M9 19c-5 1.5-5-2.5-7-3m14 6v-3.87a3.37 3.37 0 0 0-.94-2.61c3.14-.35 6.44-1.54 6.44-7A5.44 5.44 0 0 0 20 4.77 5.07 5.07 0 0 0 19.91 1S18.73.65 16 2.48a13.38 13.38 0 0 0-7 0C6.27.65 5.09 1 5.09 1A5.07 5.07 0 0 0 5 4.77a5.44 5.44 0 0 0-1.5 3.78c0 5.42 3.3 6.61 6.44 7A3.37 3.37 0 0 0 9 18.13V22"/>
+                        </svg>
+                    </a>"#, github));
+            }
+            
+            if let Some(mastodon) = &social.mastodon {
+                social_html.push_str(&format!(r#"
+                    <a href="{}" class="social-link" target="_blank" rel="noopener noreferrer" aria-label="Mastodon">
+                        <svg viewBox="0 0 24 24" fill="currentColor">
+                            <path d="M21.327 8.566c0-4.339-2.843-5.61-2.843-5.61-1.433-.658-3.894-.935-6.451-.956h-.063c-2.557.021-5.016.298-6.45.956 0 0-2.843 1.272-2.843 5.61 0 .993-.019 2.181.012 3.441.103 4.243.778 8.425 4.701 9.463 1.809.479 3.362.579 4.612.51 2.268-.126 3.541-.809 3.541-.809l-.075-1.646s-1.621.513-3.441.449c-1.804-.062-3.707-.194-3.999-2.409a4.523 4.523 0 0 1-.04-.621s1.77.433 4.014.536c1.372.063 2.658-.08 3.965-.236 2.506-.299 4.688-1.843 4.962-3.254.434-2.223.398-5.424.398-5.424zm-3.353 5.59h-2.081V9.057c0-1.075-.452-1.62-1.357-1.62-1 0-1.501.647-1.501 1.927v2.791h-2.069V9.364c0-1.28-.501-1.927-1.502-1.927-.905 0-1.357.546-1.357 1.62v5.099H6.026V8.903c0-1.074.273-1.927.823-2.558.566-.631 1.307-.955 2.228-.955 1.065 0 1.872.409 2.405 1.228l.518.869.519-.869c.533-.819 1.34-1.228 2.405-1.228.92 0 1.662.324 2.228.955.549.631.822 1.484.822 2.558v5.253z"/>
+                        </svg>
+                    </a>"#, mastodon));
+            }
+            
+            if let Some(threads) = &social.threads {
+                social_html.push_str(&format!(r#"
+                    <a href="https://threads.net/@{}" class="social-link" target="_blank" rel="noopener noreferrer" aria-label="Threads">
+                        <svg viewBox="0 0 192 192" fill="currentColor">
+                            <path d="M141.537 88.9883C140.71 88.5919 139.87 88.2104 139.019 87.8451C137.537 60.5382 122.616 44.905 97.5619 44.745C97.4484 44.7443 97.3355 44.7443 97.222 44.7443C82.2364 44.7443 69.7731 51.1409 62.102 62.7807L75.881 72.2328C81.6116 63.5383 90.6052 61.6848 97.2286 61.6848C97.3051 61.6848 97.3819 61.6848 97.4576 61.6855C105.707 61.7381 111.932 64.1366 115.961 68.814C118.893 72.2193 120.854 76.925 121.825 82.8638C114.511 81.6207 106.601 81.2385 98.145 81.7233C74.3247 83.0954 59.0111 96.9879 60.0396 116.292C60.5615 126.084 65.4397 134.508 73.775 140.011C80.8224 144.663 89.899 146.938 99.3323 146.423C111.79 145.74 121.563 140.987 128.381 132.296C133.559 125.696 136.834 117.143 138.28 106.366C144.217 109.949 148.617 114.664 151.047 120.332C155.179 129.967 155.42 145.8 142.501 158.708C131.182 170.016 117.576 174.908 97.0135 175.059C74.2042 174.89 56.9538 167.575 45.7381 153.317C35.2355 139.966 29.8077 120.682 29.6052 96C29.8077 71.3178 35.2355 52.0336 45.7381 38.6827C56.9538 24.4249 74.2039 17.11 97.0132 16.9405C119.988 17.1113 137.539 24.4614 149.184 38.788C154.894 45.8136 159.199 54.6488 162.037 64.9503L178.184 60.6422C174.744 47.9622 169.331 37.0357 161.965 27.974C147.036 9.60668 125.202 0.195148 97.0695 0H96.9569C68.8816 0.19447 47.2921 9.6418 32.7883 28.0793C19.8819 44.4864 13.2244 67.3157 13.0007 95.9325L13 96L13.0007 96.0675C13.2244 124.684 19.8819 147.514 32.7883 163.921C47.2921 182.358 68.8816 191.806 96.9569 192H97.0695C122.03 191.827 139.624 185.292 154.118 170.811C173.081 151.866 172.51 128.119 166.26 113.541C161.776 103.087 153.227 94.5962 141.537 88.9883ZM98.4405 129.507C88.0005 130.095 77.1544 125.409 76.6196 115.372C76.2232 107.93 81.9158 99.626 99.0812 98.6368C101.047 98.5234 102.976 98.468 104.871 98.468C111.106 98.468 116.939 99.0737 122.242 100.233C120.264 124.935 108.662 128.946 98.4405 129.507Z"/>
+                        </svg>
+                    </a>"#, threads));
+            }
+            
+            social_html.push_str("</div>");
+        }
+        
+        social_html
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn generate_html(&self, config: &Config, page: &PageContext, sidebar_html: &str, content_html: &str, homepage_html: Option<&str>, search_index: &str, enable_hot_reload: bool) -> String {
+        // The Docs link opens the first page
+        let first_page_url = self.first_page_id(&config.navigation)
+            .unwrap_or_else(|| "introduction/what-is-glowdoc".to_string());
+        
+        // Generate social media links HTML
+        let social_links_html = self.generate_social_links_html(&config.social);
+        let current_year = self.get_current_year();
+        
+        // Check if favicon.ico was copied next to the generated site
+        let favicon_html = if Path::new(&self.output_dir).join("favicon.ico").exists() {
+            format!("\n    <link rel=\"shortcut icon\" type=\"image/x-icon\" href=\"{}favicon.ico\">", page.root_prefix)
+        } else {
+            String::new()
+        };
+        
+        let page_title = match &page.title {
+            Some(title) => format!("{} - {}", title, config.title),
+            None => format!("{} - Modern Documentation", config.title),
+        };
+        
+        // Single-file output swaps between homepage and docs in place; multi-page output links
+        // to the real files instead
+        let home_link = match page.mode {
+            OutputMode::SingleFile => "href=\"#\" onclick=\"showHomepage()\"".to_string(),
+            OutputMode::MultiPage => format!("href=\"{}index.html\"", page.root_prefix),
+        };
+        
+        // Pages other than the homepage open straight into the docs layout
+        let (homepage_section, layout_class) = match homepage_html {
+            Some(homepage_html) => (format!("
+    <div class=\"homepage active\" id=\"homepage\">
+        <div class=\"homepage-content\">
+{}
+        </div>
+    </div>
+", homepage_html), "layout"),
+            None => (String::new(), "layout active"),
+        };
+        
+        format!("<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+    <meta charset=\"UTF-8\">
+    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">
+    <title>{}</title>
+    <meta name=\"description\" content=\"{}\">{}
+    <style>
+{}
+    </style>
+</head>
+<body>
+    <header>
+        <div class=\"container\">
+            <div class=\"header-content\">
+                <a {} class=\"logo\">{}</a>
+                <nav class=\"nav\">
+                    <button class=\"mobile-menu-toggle\" onclick=\"toggleSidebar()\">
+                        <svg width=\"16\" height=\"16\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">
+                            <line x1=\"3\" y1=\"6\" x2=\"21\" y2=\"6\"/>
+                            <line x1=\"3\" y1=\"12\" x2=\"21\" y2=\"12\"/>
+                            <line x1=\"3\" y1=\"18\" x2=\"21\" y2=\"18\"/>
+                        </svg>
+                    </button>
+                    <a {}>Home</a>
+                    <a href=\"{}\">Docs</a>
+                    <button class=\"search-toggle\" onclick=\"openSearchPalette()\" aria-label=\"Search\" aria-keyshortcuts=\"/ Control+K\">
+                        <svg width=\"16\" height=\"16\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">
+                            <circle cx=\"11\" cy=\"11\" r=\"8\"/>
+                            <path d=\"m21 21-4.35-4.35\"/>
+                        </svg>
+                    </button>
+                    <button class=\"theme-toggle\" onclick=\"toggleTheme()\">
+                        <svg width=\"16\" height=\"16\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">
+                            <circle cx=\"12\" cy=\"12\" r=\"5\"/>
+                            <path d=\"M12 1v2M12 21v2M4.22 4.22l1.42 1.42M18.36 18.36l1.42 1.42M1 12h2M21 12h2M4.22 19.78l1.42-1.42M18.36 5.64l1.42-1.42\"/>
+                        </svg>
+                    </button>
+                    {}
+                </nav>
+            </div>
+        </div>
+    </header>
+{}
+    <div class=\"{}\" id=\"docs-layout\">
+        <aside class=\"sidebar\" id=\"sidebar\">
+            <nav class=\"sidebar-nav\">
+{}
+            </nav>
+        </aside>
+
+        <main class=\"main-content\">
+            <div class=\"content-wrapper\">
+                <div class=\"content-area\">
+{}
+                </div>
+                <aside class=\"table-of-contents\" id=\"table-of-contents\">
+                    <div class=\"toc-header\">
+                        <h3>On this page</h3>
+                    </div>
+                    <nav class=\"toc-nav\" id=\"toc-nav\">
+                        <!-- Table of contents will be populated by JavaScript -->
+                    </nav>
+                </aside>
+            </div>
+        </main>
+    </div>
+
+    <footer>
+        <div class=\"container\">
+            <p>&copy; {} {}. Built with modern web standards.</p>
+        </div>
+    </footer>
+
+    <div class=\"search-palette\" id=\"search-palette\" hidden>
+        <div class=\"search-palette-backdrop\" onclick=\"closeSearchPalette()\"></div>
+        <div class=\"search-palette-dialog\" role=\"dialog\" aria-modal=\"true\" aria-label=\"Search documentation\">
+            <div class=\"search-container\">
+                <input type=\"text\" id=\"search-input\" class=\"search-input\" placeholder=\"Search pages and headings...\" autocomplete=\"off\" spellcheck=\"false\" role=\"combobox\" aria-expanded=\"false\" aria-controls=\"search-results-list\" aria-autocomplete=\"list\" oninput=\"performSearch()\">
+                <svg class=\"search-icon\" width=\"16\" height=\"16\" viewBox=\"0 0 24 24\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"2\">
+                    <circle cx=\"11\" cy=\"11\" r=\"8\"/>
+                    <path d=\"m21 21-4.35-4.35\"/>
+                </svg>
+            </div>
+            <div id=\"search-results\" class=\"search-results\" style=\"display: none;\">
+                <div id=\"search-results-list\" role=\"listbox\" aria-label=\"Search results\"></div>
+            </div>
+            <div class=\"search-palette-footer\">
+                <span><kbd>↑</kbd> <kbd>↓</kbd> to select</span>
+                <span><kbd>Enter</kbd> to open</span>
+                <span><kbd>Esc</kbd> to close</span>
+            </div>
+        </div>
+    </div>
+
+    <script>
+{}
+{}
+    </script>
+</body>
+</html>", 
+            page_title, 
+            page.description.as_deref().unwrap_or(&config.description).replace('"', "&quot;"), 
+            favicon_html,
+            css::generate_css(&config.theme),
+            home_link,
+            config.title,
+            home_link,
+            page.page_href(&first_page_url),
+            social_links_html,
+            homepage_section,
+            layout_class,
+            sidebar_html,
+            content_html,
+            current_year,
+            config.title,
+            search_index,
+            javascript::generate_javascript(enable_hot_reload, page.mode, &page.root_prefix)
+        )
+    }
+
+    /// Render the site and write it to the output directory, printing progress. Returns the
+    /// warnings of [`Site::warnings`] and any that came up while writing.
+    pub fn build(&self) -> Result<Vec<String>> {
+        if !verbosity::is_quiet() {
+            println!("Building GlowDoc...");
+        }
+        
+        let (generated_files, warnings) = self.write_site(false)?;
+        
+        if !verbosity::is_quiet() {
+            println!("Build completed successfully!");
+            println!("Generated files:");
+            self.print_generated_files(&generated_files);
+        }
+        
+        Ok(warnings)
+    }
+    
+    /// Like [`Self::build`], optionally adding the script that reloads pages for `watch`, and
+    /// only printing progress when verbose
+    pub fn build_with_hot_reload(&self, enable_hot_reload: bool) -> Result<Vec<String>> {
+        if verbosity::is_verbose() {
+            println!("Building GlowDoc...");
+        }
+        
+        let (generated_files, warnings) = self.write_site(enable_hot_reload)?;
+        
+        if verbosity::is_verbose() {
+            println!("Build completed successfully!");
+            println!("Generated files:");
+            self.print_generated_files(&generated_files);
+        }
+        
+        Ok(warnings)
+    }
+    
+    fn print_generated_files(&self, generated_files: &[String]) {
+        if verbosity::is_verbose() {
+            for file in generated_files {
+                println!("- {}", file);
+            }
+            return;
+        }
+        
+        let assets_dir = Path::new(&self.output_dir).join(assets::ASSETS_DIR);
+        let search_dir = Path::new(&self.output_dir).join(search::SEARCH_DIR);
+        let (asset_files, other_files): (Vec<&String>, Vec<&String>) = generated_files.iter()
+            .partition(|file| Path::new(file).parent() == Some(assets_dir.as_path()));
+        let (search_files, html_files): (Vec<&String>, Vec<&String>) = other_files.into_iter()
+            .partition(|file| Path::new(file).parent() == Some(search_dir.as_path()));
+        
+        match html_files.as_slice() {
+            [single] => println!("- {}", single),
+            [homepage, pages @ ..] => {
+                println!("- {}", homepage);
+                println!("- {} page files under {}", pages.len(), self.output_dir);
+            }
+            [] => {}
+        }
+        if !asset_files.is_empty() {
+            println!("- {} asset files under {}", asset_files.len(), assets_dir.display());
+        }
+        if !search_files.is_empty() {
+            println!("- {} search index files under {}", search_files.len(), search_dir.display());
+        }
+    }
+    
+    /// Load the config and check every page and internal link without writing output.
+    /// Returns the warnings, such as pages left out of the navigation; [`Error::Check`] holds
+    /// the problems found, by kind, and the warnings.
+    pub fn check(&self) -> Result<Vec<String>> {
+        let config = self.read_config()?;
+        let config_problems = self.validate_config(&config);
+        let (mut page_problems, mut plugin_problems, mut link_problems) = (Vec::new(), Vec::new(), Vec::new());
+        let mut warnings = Vec::new();
+        
+        match self.orphans(&config) {
+            Ok(orphans) => {
+                for file in orphans {
+                    warnings.push(format!("{}: not in the navigation, so it is not built", Path::new(&self.docs_path).join(file).display()));
+                }
+            }
+            Err(e) => page_problems.push(e),
+        }
+        let config = self.derive_navigation(config, &mut warnings)?;
+        
+        /// A markdown file whose links are checked
+        struct Source {
+            page: ParsedPage,
+            /// Combined ID, or `None` for entry.md
+            combined_id: Option<String>,
+        }
+        
+        let mut sources = Vec::new();
+        let (mut book, unreadable) = self.read_book(&config.navigation);
+        // Links are checked in the markdown the preprocessors produce, or the raw markdown
+        // when one fails
+        if let Err(e) = preprocess::run(&config, config.output_mode, &self.docs_path, &mut book) {
+            plugin_problems.push(e);
+        }
+        let parse = |file: &str| -> Result<ParsedPage> {
+            match book.get(file) {
+                Some(content) => ParsedPage::from_source(Path::new(&self.docs_path), file, content),
+                None => Err(Error::Markdown {
+                    path: Path::new(&self.docs_path).join(file),
+                    message: unreadable.get(file).map(|e| e.to_string()).unwrap_or_default(),
+                }),
+            }
+        };
+        
+        if Path::new(&self.entry_path).exists() {
+            match parse("entry.md") {
+                Ok(page) => sources.push(Source { page, combined_id: None }),
+                Err(e) => page_problems.push(e),
+            }
+        } else {
+            page_problems.push(self.entry_not_found());
+        }
+        
+        for (combined_id, item, _) in self.content_items(&config.navigation) {
+            let file = item.file.as_deref().unwrap_or_default();
+            let full_path = Path::new(&self.docs_path).join(file);
+            if !full_path.exists() {
+                // Reported by the config validation
+                continue;
+            }
+            match parse(file) {
+                // Drafts are not built, so links to them are broken
+                Ok(page) if page.front_matter.draft => {}
+                Ok(page) => sources.push(Source { page, combined_id: Some(combined_id) }),
+                Err(e) => page_problems.push(e),
+            }
+        }
+
+        // Header IDs of every source, shared by the link index and same-page anchors
+        let header_ids: Vec<HashSet<String>> = sources.iter()
+            .map(|source| source.page.headers().into_iter().map(|header| header.id).collect())
+            .collect();
+        
+        let mut targets = LinkTargets::new(&self.docs_path, self.ignore(&config)?);
+        for (source, headers) in sources.iter().zip(&header_ids) {
+            if let Some(combined_id) = &source.combined_id {
+                targets.add_page(&source.page.file, combined_id, headers.iter().cloned());
+            }
+        }
+        
+        for (source, headers) in sources.iter().zip(&header_ids) {
+            link_problems.extend(link_checker::check_links(&source.page, headers, &targets));
+        }
+        
+        if config_problems.is_empty() && page_problems.is_empty() && plugin_problems.is_empty() && link_problems.is_empty() {
+            return Ok(warnings);
+        }
+        Err(Error::Check { config: config_problems, pages: page_problems, plugins: plugin_problems, links: link_problems, warnings })
+    }
+    
+    /// Remove everything `build` writes, returning the removed paths. Only files GlowDoc
+    /// generates are deleted, so an output directory shared with sources stays intact.
+    pub fn clean(&self) -> Result<Vec<String>> {
+        let mut removed = Vec::new();
+        let output_dir = Path::new(&self.output_dir);
+        
+        let mut candidates = vec![PathBuf::from(&self.output_path)];
+        if let Ok(config) = self.load_config() {
+            for (combined_id, _, _) in self.content_items(&config.navigation) {
+                candidates.push(output_dir.join(combined_id).join("index.html"));
+            }
+        }
+        
+        // Copied assets carry a content hash in their name, which keeps unrelated files safe
+        if let Ok(entries) = fs::read_dir(output_dir.join(assets::ASSETS_DIR)) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_str().is_some_and(assets::is_hashed_name) {
+                    candidates.push(entry.path());
+                }
+            }
+        }
+        candidates.extend(search::shard_files(&output_dir.join(search::SEARCH_DIR)));
+        
+        candidates.push(BuildCache::path_for(&self.output_dir, &self.config_path));
+        
+        // The favicon is only a copy when its source lives elsewhere
+        let favicon = output_dir.join("favicon.ico");
+        let favicon_source = Path::new(&self.docs_path).join("favicon.ico");
+        if favicon_source.is_file() && fs::canonicalize(&favicon_source).ok() != fs::canonicalize(&favicon).ok() {
+            candidates.push(favicon);
+        }
+        
+        for path in candidates {
+            if path.is_file() {
+                fs::remove_file(&path).map_err(error::io(&path))?;
+                removed.push(path.display().to_string());
+                
+                // Drop page folders left empty, stopping at the output directory
+                let mut dir = path.parent();
+                while let Some(current) = dir {
+                    if current == output_dir || fs::remove_dir(current).is_err() {
+                        break;
+                    }
+                    dir = current.parent();
+                }
+            }
+        }
+        
+        Ok(removed)
+    }
+    
+    /// Copy `favicon.ico` from the docs root or the working directory into the output directory
+    fn copy_favicon(&self) -> Result<()> {
+        let target = Path::new(&self.output_dir).join("favicon.ico");
+        let source = [Path::new(&self.docs_path).join("favicon.ico"), PathBuf::from("favicon.ico")]
+            .into_iter()
+            .find(|source| source.is_file());
+        
+        if let Some(source) = source {
+            let same_file = fs::canonicalize(&source).ok() == fs::canonicalize(&target).ok();
+            if !same_file {
+                fs::copy(&source, &target).map_err(error::io(&target))?;
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Render the site in memory, as [`Self::build`] would write it. Nothing is written to
+    /// disk, including the build cache.
+    pub fn render(&self) -> Result<Site> {
+        self.render_site(false, &BuildCache::default())
+    }
+    
+    /// Render every page in the configured output mode, reusing what `cache` holds for pages
+    /// that did not change. Links that cannot be rewritten are returned as warnings, or fail a
+    /// strict build.
+    fn render_site(&self, enable_hot_reload: bool, cache: &BuildCache) -> Result<Site> {
+        let config = self.read_config()?;
+        let mut site_warnings = self.require_valid_config(&config)?;
+        let mut config = self.derive_navigation(config, &mut site_warnings)?;
+        self.dependencies.lock().unwrap().clear();
+        
+        // Read every page once, then extract headers and update navigation
+        let sources = self.load_pages(&config, &mut site_warnings)?;
+        self.extract_headers_and_update_navigation(&mut config.navigation, &sources, cache);
+        
+        // Generate homepage and content
+        let routes = self.page_routes(&config.navigation);
+        let assets = Assets::new(&self.docs_path, self.ignore(&config)?);
+        let warnings = Mutex::default();
+        let render = RenderContext { routes: &routes, mode: config.output_mode, assets: &assets, cache, warnings: &warnings };
+        let homepage_html = self.load_homepage(&sources, &render, &PageContext::homepage(config.output_mode))?;
+        let (pages, search_index) = self.generate_content(&config.navigation, &sources, &render)?;
+        
+        // A strict build fails on every kind of warning with the error of that kind
+        let mut missing = assets.missing();
+        let unresolved = assets.unresolved();
+        if self.strict {
+            missing.extend(unresolved.iter().map(|(file, url, _)| format!("{}: '{}'", file, url)));
+        }
+        if !missing.is_empty() {
+            return Err(Error::Assets { missing });
+        }
+        
+        // Pages render in parallel, so their warnings are sorted into a stable order
+        let mut warnings = warnings.into_inner().unwrap();
+        warnings.sort();
+        if self.strict && !warnings.is_empty() {
+            return Err(Error::Links { broken: warnings });
+        }
+        site_warnings.extend(warnings);
+        let mut unresolved: Vec<String> = unresolved.into_iter()
+            .map(|(file, url, reason)| format!("{}: link '{}' was not rewritten: {}", file, url, reason))
+            .collect();
+        unresolved.sort();
+        site_warnings.extend(unresolved);
+        
+        let mut search_files = Vec::new();
+        let documents = match config.output_mode {
+            OutputMode::SingleFile => {
+                // A single file has to work offline, so it keeps the whole index inline
+                let search_script = search_index.to_javascript()?;
+                let page = PageContext::single_file();
+                let sidebar_html = self.generate_sidebar(&config.navigation, &page);
+                let content_html = self.generate_single_file_content(&config.navigation, &pages);
+                let html = self.generate_html(&config, &page, &sidebar_html, &content_html, Some(&homepage_html), &search_script, enable_hot_reload);
+                
+                vec![SitePage { path: "index.html".to_string(), html }]
+            }
+            OutputMode::MultiPage => {
+                search_files = search_index.shards()?;
+                let search_script = format!("const searchIndexUrl = {};\n", serde_json::Value::from(search_files[0].path.as_str()));
+                
+                let page = PageContext::homepage(OutputMode::MultiPage);
+                let sidebar_html = self.generate_sidebar(&config.navigation, &page);
+                let html = self.generate_html(&config, &page, &sidebar_html, "", Some(&homepage_html), &search_script, enable_hot_reload);
+                let mut documents = vec![SitePage { path: "index.html".to_string(), html }];
+                
+                for rendered in &pages {
+                    let page = PageContext::page(rendered);
+                    let sidebar_html = self.generate_sidebar(&config.navigation, &page);
+                    let content_html = self.wrap_content_section(rendered, true);
+                    let html = self.generate_html(&config, &page, &sidebar_html, &content_html, None, &search_script, enable_hot_reload);
+                    documents.push(SitePage { path: format!("{}/index.html", rendered.id), html });
+                }
+                
+                documents
+            }
+        };
+        
+        Ok(Site {
+            pages: documents,
+            assets: assets.files()?,
+            search_index,
+            search_files,
+            config,
+            warnings: site_warnings,
+        })
+    }
+    
+    /// Render the site and write it in the configured output mode, returning the written paths
+    /// and the warnings
+    fn write_site(&self, enable_hot_reload: bool) -> Result<(Vec<String>, Vec<String>)> {
+        let cache = BuildCache::load(BuildCache::path_for(&self.output_dir, &self.config_path));
+        let mut site = self.render_site(enable_hot_reload, &cache)?;
+        
+        fs::create_dir_all(&self.output_dir).map_err(error::io(&self.output_dir))?;
+        self.copy_favicon()?;
+        
+        let mut generated_files = Vec::new();
+        if site.config.output_mode == OutputMode::MultiPage {
+            // Every build changes some shard names, so shards of earlier builds are removed
+            let current: HashSet<PathBuf> = site.search_files.iter()
+                .map(|file| Path::new(&self.output_dir).join(&file.path))
+                .collect();
+            for stale in search::shard_files(&Path::new(&self.output_dir).join(search::SEARCH_DIR)) {
+                if !current.contains(&stale) {
+                    fs::remove_file(&stale).map_err(error::io(&stale))?;
+                }
+            }
+            generated_files.extend(self.write_hashed_files(&site.search_files)?);
+        }
+        
+        for page in &site.pages {
+            // The homepage keeps the configured output path so the site root stays the same
+            let target = match page.path.as_str() {
+                "index.html" => PathBuf::from(&self.output_path),
+                path => Path::new(&self.output_dir).join(path),
+            };
+            if let Some(dir) = target.parent() {
+                fs::create_dir_all(dir).map_err(error::io(dir))?;
+            }
+            fs::write(&target, &page.html).map_err(error::io(&target))?;
+            generated_files.push(target.display().to_string());
+        }
+        
+        generated_files.extend(self.write_hashed_files(&site.assets)?);
+        
+        // A cache that cannot be written only makes the next build slower
+        if let Err(e) = cache.save() {
+            site.warnings.push(format!("Could not write the build cache: {}", e));
+        }
+        
+        Ok((generated_files, site.warnings))
+    }
+    
+    /// Write files whose names carry a content hash into the output directory, returning their
+    /// paths. A file that already exists is up to date and left alone.
+    fn write_hashed_files(&self, files: &[SiteFile]) -> Result<Vec<String>> {
+        let mut written = Vec::new();
+        for file in files {
+            let target = Path::new(&self.output_dir).join(&file.path);
+            if !target.exists() {
+                if let Some(dir) = target.parent() {
+                    fs::create_dir_all(dir).map_err(error::io(dir))?;
+                }
+                fs::write(&target, &file.content).map_err(error::io(&target))?;
+            }
+            written.push(target.display().to_string());
+        }
+        Ok(written)
+    }
+    
+    /// Watch the folders of included files outside the docs root, which the recursive watch
+    /// of the docs root does not see. Returns a warning for each folder that cannot be watched.
+    fn watch_dependencies(&self, watcher: &mut impl Watcher, watched_dirs: &mut HashSet<PathBuf>) -> Vec<String> {
+        let mut warnings = Vec::new();
+        let docs_dir = fs::canonicalize(&self.docs_path).ok();
+        let dependencies = self.dependencies.lock().unwrap().clone();
+        
+        for dependency in dependencies {
+            if docs_dir.as_ref().is_some_and(|docs| dependency.starts_with(docs)) {
+                continue;
+            }
+            let Some(dir) = dependency.parent() else {
+                continue;
+            };
+            if watched_dirs.insert(dir.to_path_buf()) {
+                if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                    warnings.push(format!("Could not watch {}: {}", dir.display(), e));
+                }
+            }
+        }
+        warnings
+    }
+    
+    /// Serve the output directory (and assets from the docs folder) on localhost:8000
+    fn spawn_http_server(&self) -> Result<tokio::task::JoinHandle<()>> {
+        let builder = Arc::new(self.clone());
+        let make_svc = make_service_fn(move |_conn| {
+            let builder = builder.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let builder = builder.clone();
+                    async move { builder.handle_http_request(req).await }
+                }))
+            }
+        });
+        
+        let addr = SocketAddr::from(([127, 0, 0, 1], 8000));
+        println!("📖 HTTP server starting on http://localhost:8000");
+        
+        let server = Server::try_bind(&addr)
+            .map_err(|e| Error::Server { address: addr.to_string(), source: io::Error::other(e) })?
+            .serve(make_svc);
+        Ok(tokio::spawn(async move {
+            if let Err(e) = server.await {
+                eprintln!("❌ HTTP server error: {}", e);
+            }
+        }))
+    }
+    
+    /// Serve the built site without watching or rebuilding
+    pub async fn serve(&self) -> Result<()> {
+        if !Path::new(&self.output_path).exists() {
+            return Err(Error::Io {
+                path: PathBuf::from(&self.output_path),
+                source: io::Error::new(io::ErrorKind::NotFound, "has not been built yet. Run 'glowdoc build' first."),
+            });
+        }
+        
+        println!("📖 Serving {} - press Ctrl+C to stop", self.output_dir);
+        // The server only stops when it panics
+        let _ = self.spawn_http_server()?.await;
+        
+        Ok(())
+    }
+    
+    /// Serve the site with hot reload and rebuild it whenever a source changes
+    pub async fn start_development_server(&self) -> Result<()> {
+        println!("🔥 Starting development server...");
+        
+        let (reload_tx, _) = broadcast::channel(16);
+        let reload_tx_clone = reload_tx.clone();
+        
+        // Start HTTP server for serving the documentation
+        let http_server = self.spawn_http_server()?;
+        
+        // Start WebSocket server for hot reload
+        let ws_server = {
+            let address = "127.0.0.1:8081";
+            let listener = TcpListener::bind(address).await
+                .map_err(|source| Error::Server { address: address.to_string(), source })?;
+            if verbosity::is_verbose() {
+                println!("🔥 WebSocket server starting on ws://localhost:8081");
+            }
+            
+            tokio::spawn(async move {
+                while let Ok((stream, addr)) = listener.accept().await {
+                    let reload_rx = reload_tx.subscribe();
+                    tokio::spawn(Self::handle_websocket(stream, addr, reload_rx));
+                }
+            })
+        };
+        
+        // Start file watcher in a separate thread
+        let docs_path = self.docs_path.clone();
+        let config_path = self.config_path.clone();
+        let builder = self.clone();
+        
+        // Output written inside the docs root must not trigger another rebuild
+        let ignored_output_dir = fs::canonicalize(&self.output_dir)
+            .ok()
+            .filter(|output_dir| fs::canonicalize(&docs_path).map(|docs| output_dir.starts_with(&docs) && *output_dir != docs).unwrap_or(false));
+        
+        // The watcher is set up before its thread starts, so failures end the command
+        let (tx, rx) = mpsc::channel();
+        let watch_error = |path: &str| {
+            let path = PathBuf::from(path);
+            move |e: notify::Error| Error::Io { path, source: io::Error::other(e) }
+        };
+        let mut watcher = recommended_watcher(move |res| {
+            match res {
+                Ok(event) => {
+                    if let Err(e) = tx.send(event) {
+                        eprintln!("Error sending file event: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Watch error: {:?}", e),
+            }
+        }).map_err(watch_error(&docs_path))?;
+        
+        watcher.watch(Path::new(&docs_path), RecursiveMode::Recursive)
+            .map_err(watch_error(&docs_path))?;
+        
+        // A config given with --config may live outside the docs root
+        let config_in_docs = fs::canonicalize(&config_path)
+            .ok()
+            .zip(fs::canonicalize(&docs_path).ok())
+            .map(|(config, docs)| config.starts_with(docs))
+            .unwrap_or(true);
+        if !config_in_docs {
+            watcher.watch(Path::new(&config_path), RecursiveMode::NonRecursive)
+                .map_err(watch_error(&config_path))?;
+        }
+        
+        thread::spawn(move || {
+            // The watcher runs for as long as the server, so it reports its own warnings
+            let print_warnings = |warnings: Vec<String>| {
+                for warning in warnings {
+                    eprintln!("⚠️  {}", warning);
+                }
+            };
+            let mut dependency_dirs = HashSet::new();
+            print_warnings(builder.watch_dependencies(&mut watcher, &mut dependency_dirs));
+            let mut ignore = builder.watch_ignore();
+            
+            println!("👀 Watching for changes in {}/", docs_path);
+            
+            // Debouncing mechanism to prevent duplicate rebuilds
+            let mut last_rebuild_times: HashMap<String, Instant> = HashMap::new();
+            let debounce_duration = Duration::from_millis(200); // 200ms debounce
+            
+            loop {
+                match rx.recv() {
+                    Ok(event) => {
+                        // Extract file paths from the event
+                        let file_paths: Vec<String> = event.paths
+                            .iter()
+                            .filter(|p| !ignored_output_dir.as_ref().is_some_and(|dir| p.starts_with(dir)))
+                            .filter(|p| !ignore::is_editor_file(p) && !ignore.is_ignored(p))
+                            // Folders of included files also report their other files
+                            .filter(|p| !p.parent().is_some_and(|dir| dependency_dirs.contains(dir)) || builder.dependencies.lock().unwrap().contains(*p))
+                            .filter_map(|p| p.to_str().map(|s| s.to_string()))
+                            .collect();
+                        
+                        if file_paths.is_empty() {
+                            continue;
+                        }
+                        
+                        if verbosity::is_verbose() {
+                            println!("📁 File change detected: {:?}", event);
+                        }
+                        
+                        // Check if we should rebuild based on debouncing
+                        let now = Instant::now();
+                        let should_rebuild = file_paths.iter().any(|path| {
+                            if let Some(&last_time) = last_rebuild_times.get(path) {
+                                now.duration_since(last_time) > debounce_duration
+                            } else {
+                                true // First time seeing this file
+                            }
+                        });
+                        
+                        if should_rebuild {
+                            // Update the last rebuild time for all affected files
+                            for path in &file_paths {
+                                last_rebuild_times.insert(path.clone(), now);
+                            }
+                            
+                            // Show a simple rebuild message for normal use
+                            let file_name = file_paths.first()
+                                .and_then(|p| std::path::Path::new(p).file_name())
+                                .and_then(|name| name.to_str())
+                                .unwrap_or("files");
+                            println!("🔄 Rebuilding after {} change...", file_name);
+                            
+                            // Rebuild the site
+                            match builder.build_with_hot_reload(true) {
+                                Err(e) => eprintln!("❌ Build failed: {}", e),
+                                Ok(warnings) => {
+                                    print_warnings(warnings);
+                                    println!("✅ Ready");
+                                    print_warnings(builder.watch_dependencies(&mut watcher, &mut dependency_dirs));
+                                    ignore = builder.watch_ignore();
+                                    
+                                    // Send reload signal to all connected clients
+                                    if let Err(e) = reload_tx_clone.send("reload".to_string()) {
+                                        if verbosity::is_verbose() {
+                                            eprintln!("Error sending reload signal: {}", e);
+                                        }
+                                    }
+                                }
+                            }
+                        } else {
+                            if verbosity::is_verbose() {
+                                println!("⏭️  Skipping rebuild (debounced)");
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("File watcher error: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+        
+        // Wait for servers to complete (they run indefinitely)
+        let _ = tokio::join!(http_server, ws_server);
+        
+        Ok(())
+    }
+    
+    async fn handle_http_request(&self, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let path = req.uri().path();
+        
+        // Handle static assets from docs directory
+        if path.starts_with("/docs/") || path.contains('.') {
+            return self.serve_static_file(path).await;
+        }
+        
+        // Directories written by the multi-page output mode serve their own index.html
+        let page_index = Path::new(&self.output_dir).join(path.trim_matches('/')).join("index.html");
+        let index_path = if page_index.is_file() { page_index } else { PathBuf::from(&self.output_path) };
+        
+        // Serve index.html for all other requests (SPA behavior)
+        match fs::read_to_string(index_path) {
+            Ok(content) => {
+                Ok(Response::builder()
+                    .header("content-type", "text/html; charset=utf-8")
+                    .header("cache-control", "no-cache, no-store, must-revalidate")
+                    .header("pragma", "no-cache")
+                    .header("expires", "0")
+                    .body(Body::from(content))
+                    .unwrap())
+            }
+            Err(_) => {
+                let error_html = r#"
+                <!DOCTYPE html>
+                <html>
+                <head>
+                    <title>GlowDoc - File Not Found</title>
+                    <style>
+                        body { font-family: Arial, sans-serif; margin: 50px; text-align: center; }
+                        .error { color: #e74c3c; }
+                        .suggestion { color: #2c3e50; margin-top: 20px; }
+                    </style>
+                </head>
+                <body>
+                    <h1 class="error">📄 Documentation not found</h1>
+                    <p>The index.html file hasn't been generated yet.</p>
+                    <div class="suggestion">
+                        <p>Make sure you have:</p>
+                        <ul style="text-align: left; display: inline-block;">
+                            <li>Created a <code>docs/config.yaml</code> file</li>
+                            <li>Added some markdown files to the <code>docs/</code> directory</li>
+                            <li>Run the build process</li>
+                        </ul>
+                        <p>Try running: <code>cargo run init-config</code> first</p>
+                    </div>
+                </body>
+                </html>
+                "#;
+                
+                Ok(Response::builder()
+                    .status(404)
+                    .header("content-type", "text/html; charset=utf-8")
+                    .body(Body::from(error_html))
+                    .unwrap())
+            }
+        }
+    }
+    
+    async fn serve_static_file(&self, path: &str) -> Result<Response<Body>, Infallible> {
+        // Clean up the path and resolve to file system
+        let clean_path = path.trim_start_matches('/');
+        let escapes_root = clean_path.split('/').any(|segment| segment == "..");
+        let output_file = Path::new(&self.output_dir).join(clean_path);
+        let file_path = if let Some(docs_relative) = clean_path.strip_prefix("docs/") {
+            // Direct reference to docs folder
+            Path::new(&self.docs_path).join(docs_relative)
+        } else if output_file.is_file() {
+            // Generated output, such as pages written by the multi-page output mode
+            output_file
+        } else {
+            // Assume it's a relative reference from within the docs
+            Path::new(&self.docs_path).join(clean_path)
+        };
+        let file_path = file_path.display().to_string();
+        
+        // Try to read the file
+        match fs::read(&file_path).ok().filter(|_| !escapes_root) {
+            Some(content) => {
+                let content_type = Self::get_content_type(&file_path);
+                
+                Ok(Response::builder()
+                    .header("content-type", content_type)
+                    .header("cache-control", "no-cache, no-store, must-revalidate")
+                    .header("pragma", "no-cache")
+                    .header("expires", "0")
+                    .body(Body::from(content))
+                    .unwrap())
+            }
+            None => {
+                let not_found_html = format!(r#"
+                <!DOCTYPE html>
+                <html>
+                <head>
+                    <title>File Not Found</title>
+                    <style>
+                        body {{ font-family: Arial, sans-serif; margin: 50px; text-align: center; }}
+                        .error {{ color: #e74c3c; }}
+                    </style>
+                </head>
+                <body>
+                    <h1 class="error">📄 File not found</h1>
+                    <p>The requested file <code>{}</code> could not be found.</p>
+                    <p><a href="/">← Back to documentation</a></p>
+                </body>
+                </html>
+                "#, file_path);
+                
+                Ok(Response::builder()
+                    .status(404)
+                    .header("content-type", "text/html; charset=utf-8")
+                    .body(Body::from(not_found_html))
+                    .unwrap())
+            }
+        }
+    }
+    
+    fn get_content_type(file_path: &str) -> &'static str {
+        let extension = file_path
+            .split('.')
+            .next_back()
+            .unwrap_or("")
+            .to_lowercase();
+        
+        match extension.as_str() {
+            // Images
+            "jpg" | "jpeg" => "image/jpeg",
+            "png" => "image/png",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            "webp" => "image/webp",
+            "ico" => "image/x-icon",
+            "bmp" => "image/bmp",
+            "tiff" | "tif" => "image/tiff",
+            
+            // Text and markup
+            "html" | "htm" => "text/html; charset=utf-8",
+            "css" => "text/css; charset=utf-8",
+            "js" | "mjs" => "application/javascript; charset=utf-8",
+            "json" => "application/json; charset=utf-8",
+            "xml" => "application/xml; charset=utf-8",
+            "txt" => "text/plain; charset=utf-8",
+            "md" => "text/markdown; charset=utf-8",
+            "yaml" | "yml" => "text/yaml; charset=utf-8",
+            
+            // Documents
+            "pdf" => "application/pdf",
+            "doc" => "application/msword",
+            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            
+            // Audio
+            "mp3" => "audio/mpeg",
+            "wav" => "audio/wav",
+            "ogg" => "audio/ogg",
+            "m4a" => "audio/mp4",
+            
+            // Video
+            "mp4" => "video/mp4",
+            "webm" => "video/webm",
+            "mov" => "video/quicktime",
+            "avi" => "video/x-msvideo",
+            
+            // Archives
+            "zip" => "application/zip",
+            "tar" => "application/x-tar",
+            "gz" => "application/gzip",
+            
+            // Fonts
+            "woff" => "font/woff",
+            "woff2" => "font/woff2",
+            "ttf" => "font/ttf",
+            "otf" => "font/otf",
+            "eot" => "application/vnd.ms-fontobject",
+            
+            // Default
+            _ => "application/octet-stream",
+        }
+    }
+    
+    async fn handle_websocket(
+        stream: TcpStream,
+        addr: std::net::SocketAddr,
+        mut reload_rx: broadcast::Receiver<String>,
+    ) {
+        let ws_stream = match accept_async(stream).await {
+            Ok(ws) => ws,
+            Err(e) => {
+                eprintln!("❌ WebSocket connection error from {}: {}", addr, e);
+                return;
+            }
+        };
+        
+        // Debug level logging for connections
+        if verbosity::is_verbose() {
+            println!("🔌 WebSocket client connected: {}", addr);
+        }
+        let (mut ws_sender, mut ws_receiver) = ws_stream.split();
+        
+        // Send initial connection confirmation
+        if let Err(e) = ws_sender.send(Message::Text("connected".to_string())).await {
+            if verbosity::is_verbose() {
+                eprintln!("❌ Failed to send initial message: {}", e);
+            }
+            return;
+        }
+        
+        // Handle incoming messages and reload signals
+        loop {
+            tokio::select! {
+                // Handle reload broadcasts
+                reload_msg = reload_rx.recv() => {
+                    match reload_msg {
+                        Ok(msg) => {
+                            if let Err(e) = ws_sender.send(Message::Text(msg)).await {
+                                if verbosity::is_verbose() {
+                                    eprintln!("❌ Failed to send reload message to {}: {}", addr, e);
+                                }
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            if verbosity::is_verbose() {
+                                eprintln!("⚠️  Client {} lagged behind, reconnection recommended", addr);
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            if verbosity::is_verbose() {
+                                println!("📡 Reload channel closed, disconnecting {}", addr);
+                            }
+                            break;
+                        }
+                    }
+                }
+                
+                // Handle incoming WebSocket messages
+                ws_msg = ws_receiver.next() => {
+                    match ws_msg {
+                        Some(Ok(Message::Close(_))) => {
+                            if verbosity::is_verbose() {
+                                println!("🔌 Client {} disconnected", addr);
+                            }
+                            break;
+                        }
+                        Some(Err(e)) => {
+                            if verbosity::is_verbose() {
+                                eprintln!("❌ WebSocket error from {}: {}", addr, e);
+                            }
+                            break;
+                        }
+                        None => {
+                            if verbosity::is_verbose() {
+                                println!("🔌 Client {} connection closed", addr);
+                            }
+                            break;
+                        }
+                        _ => {} // Ignore other message types
+                    }
+                }
+            }
+        }
+        
+        if verbosity::is_verbose() {
+            println!("🔌 WebSocket client {} disconnected", addr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A temporary docs folder holding `files`, with a builder writing next to it
+    fn temp_site(name: &str, files: &[(&str, &str)]) -> (PathBuf, GlowDocBuilder) {
+        let root = std::env::temp_dir().join(format!("glowdoc-lib-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let docs = root.join("docs");
+        for (file, content) in files {
+            let path = docs.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        let builder = GlowDocBuilder::new(docs.to_str().unwrap()).with_output_dir(root.join("out").to_str().unwrap());
+        (root, builder)
+    }
+    
+    #[test]
+    fn render_returns_the_site_and_its_warnings_without_writing() {
+        let (root, builder) = temp_site("render", &[
+            ("config.yaml", "title: T\ndescription: D\nnavigation:\n  - title: Guide\n    id: guide\n    items:\n      - title: Setup\n        id: setup\n        file: guide/setup.md\n"),
+            ("entry.md", "# Home\n"),
+            ("guide/setup.md", "# Setup\n\n![Logo](logo.png) [Gone](gone.md) [Notes](notes.txt)\n"),
+            ("guide/logo.png", "png"),
+        ]);
+        
+        let site = builder.render().unwrap();
+        let paths: Vec<&str> = site.pages.iter().map(|page| page.path.as_str()).collect();
+        assert_eq!(paths, ["index.html"]);
+        assert_eq!(site.assets.len(), 1);
+        assert!(site.assets[0].path.starts_with("assets/logo-"), "{}", site.assets[0].path);
+        assert!(site.pages[0].html.contains(&format!("src=\"{}\"", site.assets[0].path)));
+        assert_eq!(site.warnings, [
+            "guide/setup.md: link 'gone.md' was not rewritten: guide/gone.md is not in the navigation",
+            "guide/setup.md: link 'notes.txt' was not rewritten: file 'notes.txt' not found",
+        ]);
+        assert!(!root.join("out").exists(), "render writes nothing");
+        
+        // Strict builds fail on the missing file before the link
+        match builder.with_strict(true).render() {
+            Err(Error::Assets { missing }) => assert_eq!(missing, ["guide/setup.md: 'notes.txt'"]),
+            other => panic!("expected missing assets, got {:?}", other.map(|site| site.warnings)),
+        }
+        
+        fs::remove_dir_all(&root).unwrap();
+    }
+    
+    #[test]
+    fn derived_navigation_leaves_out_pages_with_invalid_front_matter() {
+        let (root, builder) = temp_site("derive-skip", &[
+            ("config.yaml", "title: T\ndescription: D\nnavigation: auto\n"),
+            ("entry.md", "# Home\n"),
+            ("guide/intro.md", "# Intro\n"),
+            ("guide/broken.md", "---\nlayout: narrow\n---\n"),
+        ]);
+        let broken = Path::new(&builder.docs_path).join("guide/broken.md");
+        
+        let site = builder.render().unwrap();
+        assert_eq!(site.config.navigation[0].items.len(), 1);
+        assert_eq!(site.warnings, [format!(
+            "{}: unknown layout 'narrow' in front matter (expected one of: default, wide), so it is not built",
+            broken.display()
+        )]);
+        match builder.with_strict(true).render() {
+            Err(Error::Markdown { path, .. }) => assert_eq!(path, broken),
+            other => panic!("expected a page error, got {:?}", other.map(|site| site.warnings)),
+        }
+        
+        fs::remove_dir_all(&root).unwrap();
+    }
+    
+    #[test]
+    fn multi_page_sites_write_a_page_per_item_linked_relative_to_it() {
+        let config = "\
+title: T
+description: D
+navigation:
+  - title: Guide
+    id: guide
+    items:
+      - title: Basics
+        id: basics
+        items:
+          - title: Setup
+            id: setup
+            file: guide/basics/setup.md
+      - title: Usage
+        id: usage
+        file: guide/usage.md
+";
+        let (root, builder) = temp_site("multi-page", &[
+            ("config.yaml", config),
+            ("entry.md", "# Home\n\n[Start](guide/basics/setup.md)\n"),
+            ("guide/basics/setup.md", "# Setup\n\nContinue with [usage](../usage.md#next-steps).\n"),
+            ("guide/usage.md", "# Usage\n\n## Next steps\n\nBack to [setup](#guide/basics/setup).\n"),
+        ]);
+        
+        // Single-file sites open on the first page, even when it is nested in a folder
+        let single_file = builder.render().unwrap();
+        assert!(single_file.pages[0].html.contains("class=\"content-section active\" id=\"guide/basics/setup\""));
+        
+        let site = builder.with_output_mode(OutputMode::MultiPage).render().unwrap();
+        assert!(site.warnings.is_empty(), "{:?}", site.warnings);
+        let html = |path: &str| site.pages.iter().find(|page| page.path == path).map(|page| page.html.as_str()).unwrap();
+        let paths: Vec<&str> = site.pages.iter().map(|page| page.path.as_str()).collect();
+        assert_eq!(paths, ["index.html", "guide/basics/setup/index.html", "guide/usage/index.html"]);
+        
+        assert!(html("index.html").contains("href=\"guide/basics/setup/index.html\""));
+        assert!(html("guide/basics/setup/index.html").contains("href=\"../../../guide/usage/index.html#next-steps\""));
+        assert!(html("guide/usage/index.html").contains("href=\"../../guide/basics/setup/index.html\""));
+        assert!(html("guide/usage/index.html").contains("id=\"next-steps\""));
+        
+        fs::remove_dir_all(&root).unwrap();
+    }
+    
+    #[test]
+    fn test_generated_index_matches_current() {
+        // Create a new builder instance
+        let builder = GlowDocBuilder::from_options(&PathOptions::default());
+        
+        // Read the current index.html content
+        let current_content = fs::read_to_string(&builder.output_path)
+            .expect("Failed to read current index.html file");
+        
+        // Load config and generate new content
+        let mut config = builder.load_config()
+            .expect("Failed to load config");
+        
+        // Extract headers and update navigation
+        let cache = BuildCache::default();
+        let sources = builder.load_pages(&config, &mut Vec::new())
+            .expect("Failed to load pages");
+        builder.extract_headers_and_update_navigation(&mut config.navigation, &sources, &cache);
+        
+        // Generate all components
+        let page = PageContext::single_file();
+        let routes = builder.page_routes(&config.navigation);
+        let assets = Assets::new(&builder.docs_path, builder.ignore(&config).expect("Failed to read the ignore patterns"));
+        let warnings = Mutex::default();
+        let render = RenderContext { routes: &routes, mode: OutputMode::SingleFile, assets: &assets, cache: &cache, warnings: &warnings };
+        let homepage_html = builder.load_homepage(&sources, &render, &page)
+            .expect("Failed to load homepage");
+        let sidebar_html = builder.generate_sidebar(&config.navigation, &page);
+        let (pages, search_index) = builder.generate_content(&config.navigation, &sources, &render)
+            .expect("Failed to generate content");
+        let search_script = search_index.to_javascript()
+            .expect("Failed to serialize the search index");
+        let content_html = builder.generate_single_file_content(&config.navigation, &pages);
+        
+        // Generate the complete HTML (without hot reload)
+        let generated_content = builder.generate_html(
+            &config, 
+            &page,
+            &sidebar_html, 
+            &content_html, 
+            Some(&homepage_html), 
+            &search_script, 
+            false
+        );
+        
+        // Compare the contents
+        assert_eq!(
+            current_content.trim(), 
+            generated_content.trim(),
+            "Generated index.html content does not match the current index.html file"
+        );
+    }
+}
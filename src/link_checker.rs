@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use pulldown_cmark::{Event, Tag};
+use regex::Regex;
+use crate::assets;
+use crate::ignore::Ignore;
+use crate::page::ParsedPage;
+use crate::routes::{PageRoutes, Route};
+
+/// Every link target in the site: combined page IDs mapped to the header IDs on that page,
+/// the page files that relative `.md` links resolve to, and the docs folder that images and
+/// downloads are looked up in
+#[derive(Debug)]
+pub struct LinkTargets {
+    pages: HashMap<String, HashSet<String>>,
+    routes: PageRoutes,
+    docs_path: PathBuf,
+    ignore: Ignore,
+}
+
+impl LinkTargets {
+    pub fn new<P: Into<PathBuf>>(docs_path: P, ignore: Ignore) -> Self {
+        LinkTargets {
+            pages: HashMap::new(),
+            routes: PageRoutes::default(),
+            docs_path: docs_path.into(),
+            ignore,
+        }
+    }
+
+    pub fn add_page(&mut self, file: &str, combined_id: &str, header_ids: impl IntoIterator<Item = String>) {
+        self.pages.insert(combined_id.to_string(), header_ids.into_iter().collect());
+        self.routes.add(file, combined_id);
+    }
+}
+
+/// A link whose page or heading does not exist
+#[derive(Debug)]
+pub struct BrokenLink {
+    /// Markdown file the link is in, relative to the docs folder
+    pub file: String,
+    /// 1-based line of the link in the source file, counting any front matter
+    pub line: usize,
+    pub target: String,
+    pub reason: String,
+}
+
+/// Check the internal links of one markdown file. `own_headers` are the header IDs that
+/// same-page `#heading` links resolve to.
+pub fn check_links(page: &ParsedPage, own_headers: &HashSet<String>, targets: &LinkTargets) -> Vec<BrokenLink> {
+    let file = page.file.as_str();
+    static HTML_ATTRIBUTE: OnceLock<Regex> = OnceLock::new();
+    let html_attribute = HTML_ATTRIBUTE.get_or_init(|| Regex::new(r#"\b(?:src|poster|href)=["']([^"']*)["']"#).unwrap());
+
+    let mut broken = Vec::new();
+    for (event, range) in page.events() {
+        let line = page.line_of(range.start);
+        let mut report = |target: &str, reason: String| {
+            broken.push(BrokenLink { file: file.to_string(), line, target: target.to_string(), reason });
+        };
+
+        match event {
+            Event::Start(Tag::Link(_, destination, _)) => {
+                let reason = match assets::resolve(&targets.docs_path, &targets.ignore, file, destination) {
+                    Some(asset) => asset.err(),
+                    None => resolve(file, destination, own_headers, targets),
+                };
+                if let Some(reason) = reason {
+                    report(destination, reason);
+                }
+            }
+            Event::Start(Tag::Image(_, destination, _)) => {
+                if let Some(Err(reason)) = assets::resolve(&targets.docs_path, &targets.ignore, file, destination) {
+                    report(destination, reason);
+                }
+            }
+            Event::Html(html) => {
+                for caps in html_attribute.captures_iter(html) {
+                    if let Some(Err(reason)) = assets::resolve(&targets.docs_path, &targets.ignore, file, &caps[1]) {
+                        report(&caps[1], reason);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    broken
+}
+
+/// Why an in-site link does not resolve, or `None` when it does or points elsewhere
+fn resolve(file: &str, destination: &str, own_headers: &HashSet<String>, targets: &LinkTargets) -> Option<String> {
+    let route = match targets.routes.resolve(file, destination) {
+        Some(Ok(route)) => route,
+        Some(Err(reason)) => return Some(reason),
+        None => match Route::from_hash(destination) {
+            Some(route) => route,
+            // `#heading` without a page points into the current page; external URLs and
+            // other files are left to the browser
+            None => {
+                let heading = destination.strip_prefix('#')?;
+                return (!heading.is_empty() && !own_headers.contains(heading))
+                    .then(|| format!("no heading '{}' on this page", heading));
+            }
+        },
+    };
+
+    let Some(page_headers) = targets.pages.get(&route.page_id) else {
+        return Some(format!("no page '{}' in the navigation", route.page_id));
+    };
+
+    match route.fragment {
+        Some(fragment) if !page_headers.contains(&fragment) => {
+            Some(format!("no heading '{}' on page '{}'", fragment, route.page_id))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// `(line, target, reason)` of the broken links of `markdown`, checked against a site
+    /// with the page `guide/setup` (file `guide/setup.md`) holding the heading `install`
+    fn broken_links(markdown: &str) -> Vec<(usize, String, String)> {
+        let mut targets = LinkTargets::new("docs", Ignore::default());
+        targets.add_page("guide/setup.md", "guide/setup", ["install".to_string()]);
+        let page = ParsedPage::from_source(Path::new("docs"), "guide/intro.md", markdown).unwrap();
+        let own_headers = page.headers().into_iter().map(|header| header.id).collect();
+        check_links(&page, &own_headers, &targets)
+            .into_iter()
+            .map(|link| (link.line, link.target, link.reason))
+            .collect()
+    }
+
+    #[test]
+    fn hash_routes_resolve_to_pages() {
+        assert!(broken_links("[Setup](#guide/setup) and [Install](#guide/setup#install)\n").is_empty());
+        assert_eq!(broken_links("[Gone](#guide/gone)\n"), [
+            (1, "#guide/gone".to_string(), "no page 'guide/gone' in the navigation".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn hash_routes_check_their_heading() {
+        assert_eq!(broken_links("[Upgrade](#guide/setup#upgrade)\n"), [
+            (1, "#guide/setup#upgrade".to_string(), "no heading 'upgrade' on page 'guide/setup'".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn bare_headings_point_into_the_same_page() {
+        assert!(broken_links("# Overview\n\nSee [above](#overview).\n").is_empty());
+        assert_eq!(broken_links("# Overview\n\nSee [below](#details).\n"), [
+            (3, "#details".to_string(), "no heading 'details' on this page".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn lines_after_multi_line_html_count_every_line() {
+        let markdown = "# Intro\n\n<div class=\"hero\">\n  <p>\n    Welcome\n  </p>\n</div>\n\n[Missing](#nowhere)\n";
+        assert_eq!(broken_links(markdown), [
+            (9, "#nowhere".to_string(), "no heading 'nowhere' on this page".to_string()),
+        ]);
+    }
+}
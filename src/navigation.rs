@@ -0,0 +1,141 @@
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeSeq, Serializer};
+use serde::{Deserialize, Serialize};
+use crate::NavigationSection;
+
+/// The `navigation` of `config.yaml`: the sections listed in it, and where the sections
+/// derived from the docs folder go. `navigation: auto`, or no `navigation` key, derives all of
+/// them; an `auto` entry in the list derives the pages the listed sections leave out.
+#[derive(Debug, Clone, Default)]
+pub struct Navigation {
+    pub sections: Vec<NavigationSection>,
+    /// Index in `sections` where the derived sections are inserted, when navigation is derived
+    pub auto: Option<usize>,
+}
+
+impl Navigation {
+    /// Navigation derived entirely from the docs folder
+    pub fn derived() -> Self {
+        Navigation { sections: Vec::new(), auto: Some(0) }
+    }
+}
+
+impl From<Vec<NavigationSection>> for Navigation {
+    fn from(sections: Vec<NavigationSection>) -> Self {
+        Navigation { sections, auto: None }
+    }
+}
+
+impl Deref for Navigation {
+    type Target = Vec<NavigationSection>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sections
+    }
+}
+
+impl DerefMut for Navigation {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sections
+    }
+}
+
+const AUTO: &str = "auto";
+
+impl Serialize for Navigation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.auto {
+            Some(_) if self.sections.is_empty() => serializer.serialize_str(AUTO),
+            auto => {
+                let mut seq = serializer.serialize_seq(Some(self.sections.len() + usize::from(auto.is_some())))?;
+                for (index, section) in self.sections.iter().enumerate() {
+                    if auto == Some(index) {
+                        seq.serialize_element(AUTO)?;
+                    }
+                    seq.serialize_element(section)?;
+                }
+                if auto == Some(self.sections.len()) {
+                    seq.serialize_element(AUTO)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Navigation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(NavigationVisitor)
+    }
+}
+
+struct NavigationVisitor;
+
+impl<'de> Visitor<'de> for NavigationVisitor {
+    type Value = Navigation;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a list of sections or `auto`")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Navigation, E> {
+        match value {
+            AUTO => Ok(Navigation::derived()),
+            _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+        }
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Navigation, E> {
+        Ok(Navigation::derived())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Navigation, A::Error> {
+        let mut navigation = Navigation::default();
+        while let Some(entry) = seq.next_element::<Entry>()? {
+            match entry {
+                Entry::Section(section) => navigation.sections.push(section),
+                Entry::Auto if navigation.auto.is_some() => {
+                    return Err(de::Error::custom("`auto` may only be listed once"));
+                }
+                Entry::Auto => navigation.auto = Some(navigation.sections.len()),
+            }
+        }
+        Ok(navigation)
+    }
+}
+
+/// An item of the `navigation` list
+enum Entry {
+    Section(NavigationSection),
+    Auto,
+}
+
+impl<'de> Deserialize<'de> for Entry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntryVisitor;
+
+        impl<'de> Visitor<'de> for EntryVisitor {
+            type Value = Entry;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a section or `auto`")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Entry, E> {
+                match value {
+                    AUTO => Ok(Entry::Auto),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+                }
+            }
+
+            // Sections are read through the YAML deserializer, so their errors keep their paths
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Entry, A::Error> {
+                NavigationSection::deserialize(de::value::MapAccessDeserializer::new(map)).map(Entry::Section)
+            }
+        }
+
+        deserializer.deserialize_any(EntryVisitor)
+    }
+}
@@ -0,0 +1,416 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag};
+use crate::admonition;
+use crate::error::{Error, Result};
+use crate::assets;
+use crate::frontmatter::{self, FrontMatter};
+use crate::include;
+use crate::search::SearchRecord;
+use crate::HeaderItem;
+
+/// A markdown file read once per build. Headers, rendering, the search index and the link
+/// checker all work from this model instead of reading and parsing the file again.
+#[derive(Debug)]
+pub struct ParsedPage {
+    /// Path relative to the docs folder
+    pub file: String,
+    /// File content with its includes expanded, including any front matter
+    pub source: String,
+    pub front_matter: FrontMatter,
+    /// Hash of `source`, used as the build cache key
+    pub content_hash: String,
+    /// Files pulled in by `{{#include}}` directives, which a rebuild has to watch
+    pub dependencies: Vec<PathBuf>,
+    /// Text of each include in `source`, with the lines it added, so lines map back to the file
+    includes: Vec<(Range<usize>, usize)>,
+    /// Byte offset of the markdown following the front matter
+    body_start: usize,
+    /// Parsed on first use, since pages reused from the build cache never need their events
+    parsed: OnceLock<Parsed>,
+}
+
+#[derive(Debug)]
+struct Parsed {
+    events: Vec<(Event<'static>, Range<usize>)>,
+    /// One entry per heading event, in document order
+    headings: Vec<Heading>,
+}
+
+/// A heading of the page with the ID it is rendered and linked with
+#[derive(Debug)]
+pub struct Heading {
+    pub level: u8,
+    /// Text of the heading, including inline code and the text of links and emphasis
+    pub title: String,
+    /// `{#custom-id}` from the heading, or a slug of the title made unique within the page
+    pub id: String,
+    /// `{.class}` attributes from the heading
+    pub classes: Vec<String>,
+}
+
+impl ParsedPage {
+    /// Parse `source`, the markdown of `file` (relative to `docs_path`): expand its includes
+    /// and split off its front matter
+    pub fn from_source(docs_path: &Path, file: &str, source: &str) -> Result<Self> {
+        let error = |message| Error::Markdown { path: docs_path.join(file), message };
+        let expanded = include::expand(&docs_path.join(file), source).map_err(error)?;
+        let source = expanded.source;
+        let (front_matter, body) = frontmatter::split(&source).map_err(error)?;
+        let body_start = source.len() - body.len();
+
+        Ok(ParsedPage {
+            file: file.to_string(),
+            content_hash: assets::content_hash(source.as_bytes()),
+            dependencies: expanded.dependencies,
+            includes: expanded.spans,
+            front_matter,
+            body_start,
+            source,
+            parsed: OnceLock::new(),
+        })
+    }
+
+    /// The markdown after the front matter
+    pub fn body(&self) -> &str {
+        &self.source[self.body_start..]
+    }
+
+    fn parsed(&self) -> &Parsed {
+        self.parsed.get_or_init(|| parse(self.body()))
+    }
+
+    /// Markdown events with their byte ranges in [`ParsedPage::body`]
+    pub fn events(&self) -> &[(Event<'static>, Range<usize>)] {
+        &self.parsed().events
+    }
+
+    /// Every heading in document order, including headings without text
+    pub fn headings(&self) -> &[Heading] {
+        &self.parsed().headings
+    }
+
+    /// Events to render, with each heading carrying its ID and classes
+    pub fn render_events(&self) -> impl Iterator<Item = Event<'_>> {
+        let mut headings = self.headings().iter();
+        self.events().iter().map(move |(event, _)| match event {
+            Event::Start(Tag::Heading(level, _, _)) => match headings.next() {
+                Some(heading) => Event::Start(Tag::Heading(
+                    *level,
+                    Some(heading.id.as_str()),
+                    heading.classes.iter().map(String::as_str).collect(),
+                )),
+                None => event.clone(),
+            },
+            event => event.clone(),
+        })
+    }
+
+    /// 1-based line in the file, counting the front matter, of an offset into the body.
+    /// Offsets in included text map to the line of the include directive.
+    pub fn line_of(&self, body_offset: usize) -> usize {
+        let offset = self.body_start + body_offset;
+        let mut line = self.source[..offset].matches('\n').count() + 1;
+        for (span, added_lines) in &self.includes {
+            if span.end <= offset {
+                line -= added_lines;
+            } else if span.start < offset {
+                line -= self.source[span.start..offset].matches('\n').count();
+            }
+        }
+        line
+    }
+
+    /// Headings with text, as listed in the navigation and matched by links
+    pub fn headers(&self) -> Vec<HeaderItem> {
+        self.headings().iter()
+            .filter(|heading| !heading.title.is_empty())
+            .map(|heading| HeaderItem {
+                title: heading.title.clone(),
+                id: heading.id.clone(),
+                level: heading.level,
+            })
+            .collect()
+    }
+
+    /// The readable text of the page without markdown syntax, split at every heading below
+    /// the page title. The first record holds the title and any text before the first split.
+    pub fn search_records(&self) -> Vec<SearchRecord> {
+        let mut records = vec![SearchRecord { heading: None, heading_title: None, text: String::new() }];
+        let mut headings = self.headings().iter();
+        let mut in_heading = false;
+
+        for (event, _) in self.events() {
+            let text = &mut records.last_mut().unwrap().text;
+            match event {
+                Event::Start(Tag::Heading(level, _, _)) => {
+                    let heading = headings.next();
+                    if *level != HeadingLevel::H1 {
+                        if let Some(heading) = heading {
+                            records.push(SearchRecord {
+                                heading: Some(heading.id.clone()),
+                                heading_title: Some(heading.title.clone()),
+                                text: String::new(),
+                            });
+                            in_heading = true;
+                        }
+                    }
+                }
+                Event::End(Tag::Heading(..)) if in_heading => in_heading = false,
+                // Heading titles are indexed on their own
+                Event::Text(_) | Event::Code(_) if in_heading => {}
+                Event::Text(content) | Event::Code(content) => text.push_str(content),
+                Event::SoftBreak => text.push(' '),
+                Event::HardBreak | Event::End(Tag::Paragraph | Tag::Heading(..) | Tag::Item | Tag::CodeBlock(_) | Tag::TableRow | Tag::TableHead)
+                    if !text.is_empty() && !text.ends_with('\n') => text.push('\n'),
+                Event::End(Tag::TableCell) => text.push(' '),
+                _ => {}
+            }
+        }
+
+        for record in &mut records {
+            record.text = record.text.trim_end().to_string();
+        }
+        records
+    }
+}
+
+/// The GitHub-flavored markdown extensions every page is parsed with
+pub fn markdown_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    options
+}
+
+/// Parse a markdown body, detaching the events from it, assigning every heading its ID and
+/// turning callout syntax into callout markup
+fn parse(body: &str) -> Parsed {
+    let fenced = admonition::Fenced::new(body);
+    let mut events = Vec::new();
+    let mut headings = Vec::new();
+    let mut custom_ids = Vec::new();
+    let mut current: Option<Heading> = None;
+
+    for (event, range) in Parser::new_ext(&fenced.source, markdown_options()).into_offset_iter() {
+        let range = fenced.original_offset(range.start)..fenced.original_offset(range.end);
+        match &event {
+            Event::Start(Tag::Heading(level, id, classes)) => {
+                custom_ids.push(id.map(str::to_string));
+                current = Some(Heading {
+                    level: heading_level(*level),
+                    title: String::new(),
+                    id: String::new(),
+                    classes: classes.iter().map(|class| class.to_string()).collect(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = &mut current {
+                    heading.title.push_str(text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => headings.extend(current.take()),
+            _ => {}
+        }
+        events.push((owned_event(event), range));
+    }
+
+    // Explicit IDs are taken first, so a generated slug never shadows one. An explicit ID
+    // given twice is suffixed like a repeated slug.
+    let mut used = HashSet::new();
+    let custom_ids: Vec<Option<String>> = custom_ids.into_iter()
+        .map(|id| id.map(|id| unique_id(id, &mut used)))
+        .collect();
+    for (heading, custom_id) in headings.iter_mut().zip(custom_ids) {
+        heading.id = match custom_id {
+            Some(id) => id,
+            None => unique_slug(&heading.title, &mut used),
+        };
+    }
+
+    Parsed { events: admonition::render(events, &fenced, body), headings }
+}
+
+/// Slug of `title`, made unique with [`unique_id`]
+fn unique_slug(title: &str, used: &mut HashSet<String>) -> String {
+    let slug = match slugify(title) {
+        slug if slug.is_empty() => "section".to_string(),
+        slug => slug,
+    };
+    unique_id(slug, used)
+}
+
+/// `base`, suffixed with `-1`, `-2`, ... when an earlier heading already uses it
+fn unique_id(base: String, used: &mut HashSet<String>) -> String {
+    let mut id = base.clone();
+    let mut suffix = 0;
+    while used.contains(&id) {
+        suffix += 1;
+        id = format!("{}-{}", base, suffix);
+    }
+    used.insert(id.clone());
+    id
+}
+
+/// Lowercase `text` and join its alphanumeric runs with `-`
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Detach an event from the source text so it can be kept in the page model
+fn owned_event(event: Event) -> Event<'static> {
+    match event {
+        Event::Start(tag) => Event::Start(owned_tag(tag)),
+        Event::End(tag) => Event::End(owned_tag(tag)),
+        Event::Text(text) => Event::Text(owned_str(text)),
+        Event::Code(code) => Event::Code(owned_str(code)),
+        Event::Html(html) => Event::Html(owned_str(html)),
+        Event::FootnoteReference(label) => Event::FootnoteReference(owned_str(label)),
+        Event::SoftBreak => Event::SoftBreak,
+        Event::HardBreak => Event::HardBreak,
+        Event::Rule => Event::Rule,
+        Event::TaskListMarker(checked) => Event::TaskListMarker(checked),
+    }
+}
+
+fn owned_tag(tag: Tag) -> Tag<'static> {
+    match tag {
+        Tag::Paragraph => Tag::Paragraph,
+        // The ID and classes live in [`Heading`] and are put back by `render_events`
+        Tag::Heading(level, _, _) => Tag::Heading(level, None, Vec::new()),
+        Tag::BlockQuote => Tag::BlockQuote,
+        Tag::CodeBlock(CodeBlockKind::Indented) => Tag::CodeBlock(CodeBlockKind::Indented),
+        Tag::CodeBlock(CodeBlockKind::Fenced(info)) => Tag::CodeBlock(CodeBlockKind::Fenced(owned_str(info))),
+        Tag::List(start) => Tag::List(start),
+        Tag::Item => Tag::Item,
+        Tag::FootnoteDefinition(label) => Tag::FootnoteDefinition(owned_str(label)),
+        Tag::Table(alignments) => Tag::Table(alignments),
+        Tag::TableHead => Tag::TableHead,
+        Tag::TableRow => Tag::TableRow,
+        Tag::TableCell => Tag::TableCell,
+        Tag::Emphasis => Tag::Emphasis,
+        Tag::Strong => Tag::Strong,
+        Tag::Strikethrough => Tag::Strikethrough,
+        Tag::Link(link_type, destination, title) => Tag::Link(link_type, owned_str(destination), owned_str(title)),
+        Tag::Image(link_type, destination, title) => Tag::Image(link_type, owned_str(destination), owned_str(title)),
+    }
+}
+
+fn owned_str(text: CowStr) -> CowStr<'static> {
+    match text {
+        CowStr::Borrowed(text) => CowStr::Boxed(text.into()),
+        CowStr::Boxed(text) => CowStr::Boxed(text),
+        CowStr::Inlined(text) => CowStr::Inlined(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A docs folder holding `files`, given as `(path, content)`
+    fn docs_folder(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let docs = std::env::temp_dir().join(format!("glowdoc-page-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&docs);
+        for (path, content) in files {
+            fs::create_dir_all(docs.join(path).parent().unwrap()).unwrap();
+            fs::write(docs.join(path), content).unwrap();
+        }
+        docs
+    }
+
+    #[test]
+    fn lines_count_front_matter_and_map_includes_to_their_directive() {
+        let page_source = "---\ntitle: Setup\ntags: [a]\n---\n# Setup\n\n{{#include parts/steps.md}}\n\nAfter [the steps](next.md).\n";
+        let docs = docs_folder("lines", &[
+            ("guide/parts/steps.md", "1. One\n2. Two\n\n   More of [two](two.md)\n"),
+        ]);
+        let page = ParsedPage::from_source(&docs, "guide/setup.md", page_source).unwrap();
+        fs::remove_dir_all(&docs).unwrap();
+
+        let line = |text: &str| page.line_of(page.body().find(text).unwrap());
+        assert_eq!(line("# Setup"), 5);
+        // Every line of the included file reports the directive's line
+        assert_eq!(line("1. One"), 7);
+        assert_eq!(line("More of"), 7);
+        // Lines after the include are counted as in the file, not the expanded text
+        assert_eq!(line("After"), 9);
+        assert_eq!(page.dependencies.len(), 1);
+    }
+
+    fn heading_ids(body: &str) -> Vec<(String, String)> {
+        let page = ParsedPage::from_source(Path::new("docs"), "page.md", body).unwrap();
+        page.headings().iter().map(|heading| (heading.title.clone(), heading.id.clone())).collect()
+    }
+
+    fn heading(title: &str, id: &str) -> (String, String) {
+        (title.to_string(), id.to_string())
+    }
+
+    #[test]
+    fn repeated_headings_get_numbered_ids() {
+        assert_eq!(heading_ids("## Examples\n\n## Examples\n\n### Examples!\n\n##\n"), [
+            heading("Examples", "examples"),
+            heading("Examples", "examples-1"),
+            heading("Examples!", "examples-2"),
+            heading("", "section"),
+        ]);
+    }
+
+    #[test]
+    fn custom_ids_win_over_generated_slugs() {
+        assert_eq!(heading_ids("## Intro\n\n## Overview {#intro}\n\n## Intro 1\n\n## Setup {#setup .wide}\n"), [
+            heading("Intro", "intro-1"),
+            heading("Overview", "intro"),
+            // The slug of this heading is taken by the renumbered one above
+            heading("Intro 1", "intro-1-1"),
+            heading("Setup", "setup"),
+        ]);
+        let page = ParsedPage::from_source(Path::new("docs"), "page.md", "## Setup {#setup .wide}\n").unwrap();
+        assert_eq!(page.headings()[0].classes, ["wide"]);
+    }
+
+    #[test]
+    fn repeated_custom_ids_are_numbered() {
+        assert_eq!(heading_ids("## Linux {#install}\n\n## macOS {#install}\n\n## Install\n"), [
+            heading("Linux", "install"),
+            heading("macOS", "install-1"),
+            heading("Install", "install-2"),
+        ]);
+        let page = ParsedPage::from_source(Path::new("docs"), "page.md", "## A {#same}\n\n## B {#same}\n").unwrap();
+        let mut html = String::new();
+        pulldown_cmark::html::push_html(&mut html, page.render_events());
+        assert_eq!(html, "<h2 id=\"same\">A</h2>\n<h2 id=\"same-1\">B</h2>\n");
+    }
+
+    #[test]
+    fn heading_titles_keep_the_text_of_inline_markup() {
+        assert_eq!(heading_ids("## Run `glowdoc` with *care* and [**links**](next.md)\n"), [
+            heading("Run glowdoc with care and links", "run-glowdoc-with-care-and-links"),
+        ]);
+    }
+}
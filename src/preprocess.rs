@@ -0,0 +1,190 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+use crate::{Config, OutputMode};
+
+/// An external command declared under `preprocessors:` in `config.yaml`. It reads the book as
+/// JSON on stdin and writes the pages it changed as JSON on stdout.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Preprocessor {
+    pub name: String,
+    /// Run through the shell from the working directory, e.g. `python3 scripts/stamp.py`
+    pub command: String,
+    /// Output modes the preprocessor runs for; all of them when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renderers: Vec<OutputMode>,
+}
+
+/// What a preprocessor receives
+#[derive(Serialize)]
+struct BookInput<'a> {
+    glowdoc_version: &'a str,
+    /// Output mode being built, e.g. `single-file`
+    renderer: OutputMode,
+    /// The docs folder the page files are relative to
+    root: &'a str,
+    config: &'a Config,
+    pages: Vec<PageSource<'a>>,
+}
+
+#[derive(Serialize)]
+struct PageSource<'a> {
+    file: &'a str,
+    source: &'a str,
+}
+
+/// What a preprocessor returns: the pages it changed, with their new markdown
+#[derive(Deserialize)]
+struct BookOutput {
+    pages: Vec<ChangedPage>,
+}
+
+#[derive(Deserialize)]
+struct ChangedPage {
+    file: String,
+    source: String,
+}
+
+/// Run the preprocessors of `config` that apply to `renderer`, in the order they are listed,
+/// each on the pages the previous one returned. `pages` maps files relative to `root`
+/// (including `entry.md` for the homepage) to their markdown.
+pub fn run(config: &Config, renderer: OutputMode, root: &str, pages: &mut BTreeMap<String, String>) -> Result<()> {
+    for preprocessor in &config.preprocessors {
+        if !preprocessor.renderers.is_empty() && !preprocessor.renderers.contains(&renderer) {
+            continue;
+        }
+
+        let error = |message: String| Error::Plugin {
+            name: preprocessor.name.clone(),
+            command: preprocessor.command.clone(),
+            message,
+        };
+        let input = BookInput {
+            glowdoc_version: env!("CARGO_PKG_VERSION"),
+            renderer,
+            root,
+            config,
+            pages: pages.iter().map(|(file, source)| PageSource { file, source }).collect(),
+        };
+        let input = serde_json::to_vec(&input).map_err(|e| error(format!("its input could not be written: {}", e)))?;
+        let output = execute(preprocessor, &input).map_err(error)?;
+
+        for page in output.pages {
+            match pages.get_mut(&page.file) {
+                Some(source) => *source = page.source,
+                None => return Err(error(format!("it returned '{}', which is not a page of the book", page.file))),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn execute(preprocessor: &Preprocessor, input: &[u8]) -> Result<BookOutput, String> {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", &preprocessor.command]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", &preprocessor.command]);
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not start it: {}", e))?;
+
+    // Written from another thread, so a plugin that answers before reading everything
+    // cannot block on a full stdout pipe while GlowDoc blocks on its stdin
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() {
+        let status = output.status.code().map_or("a signal".to_string(), |code| format!("exit code {}", code));
+        return Err(format!("it stopped with {}{}", status, indented(&stderr)));
+    }
+    // A plugin may legitimately exit without reading its input
+    let _ = writer.join();
+
+    // Warnings a successful plugin prints are passed on
+    if !stderr.trim().is_empty() {
+        eprint!("{}", stderr);
+    }
+
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("its output is not a valid book: {}{}", e, indented(&String::from_utf8_lossy(&output.stdout))))
+}
+
+/// Command output shown under an error, or nothing when there is none
+fn indented(text: &str) -> String {
+    let text = text.trim();
+    if text.is_empty() {
+        return String::new();
+    }
+    let lines: Vec<String> = text.lines().take(20).map(|line| format!("    {}", line)).collect();
+    format!("\n{}", lines.join("\n"))
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use super::*;
+
+    fn config_with(preprocessors: &str) -> Config {
+        serde_yaml::from_str(&format!("title: Docs\ndescription: Test\nnavigation: []\npreprocessors:\n{}", preprocessors)).unwrap()
+    }
+
+    fn pages() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("entry.md".to_string(), "# Hello\n".to_string()),
+            ("guide/setup.md".to_string(), "Hello from setup\n".to_string()),
+        ])
+    }
+
+    #[test]
+    fn pages_round_trip_through_a_preprocessor() {
+        let config = config_with("  - name: echo\n    command: cat\n");
+        let mut book = pages();
+        run(&config, OutputMode::SingleFile, "docs", &mut book).unwrap();
+        assert_eq!(book, pages());
+    }
+
+    #[test]
+    fn preprocessors_only_run_for_their_renderers() {
+        let config = config_with("  - name: greet\n    command: sed 's/Hello/Goodbye/g'\n    renderers: [multi-page]\n");
+
+        let mut book = pages();
+        run(&config, OutputMode::SingleFile, "docs", &mut book).unwrap();
+        assert_eq!(book, pages());
+
+        run(&config, OutputMode::MultiPage, "docs", &mut book).unwrap();
+        assert_eq!(book["entry.md"], "# Goodbye\n");
+        assert_eq!(book["guide/setup.md"], "Goodbye from setup\n");
+    }
+
+    #[test]
+    fn failing_preprocessors_report_their_stderr() {
+        let config = config_with("  - name: broken\n    command: echo 'no such theme' >&2; exit 3\n");
+        match run(&config, OutputMode::SingleFile, "docs", &mut pages()) {
+            Err(Error::Plugin { name, command, message }) => {
+                assert_eq!(name, "broken");
+                assert_eq!(command, "echo 'no such theme' >&2; exit 3");
+                assert_eq!(message, "it stopped with exit code 3\n    no such theme");
+            }
+            other => panic!("expected a plugin error, got {:?}", other),
+        }
+
+        let config = config_with("  - name: unknown\n    command: echo '{\"pages\":[{\"file\":\"other.md\",\"source\":\"\"}]}'\n");
+        let error = run(&config, OutputMode::SingleFile, "docs", &mut pages()).unwrap_err();
+        assert!(error.to_string().contains("it returned 'other.md', which is not a page of the book"), "{}", error);
+    }
+}
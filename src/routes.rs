@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+/// A page in the site, optionally scrolled to a heading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    /// Combined ID, e.g. `getting-started/setup/setup-installation`
+    pub page_id: String,
+    pub fragment: Option<String>,
+}
+
+impl Route {
+    /// Parse a hash route such as `#section/page` or `#section/page#heading`; a trailing `/`
+    /// is dropped. Links like `#heading` that stay on the current page are not routes.
+    pub fn from_hash(destination: &str) -> Option<Route> {
+        let route = destination.strip_prefix('#')?.replace("%23", "#");
+        let (page_id, fragment) = match route.split_once('#') {
+            Some((page_id, fragment)) => (page_id.trim_end_matches('/').to_string(), Some(fragment.to_string())),
+            None => (route.trim_end_matches('/').to_string(), None),
+        };
+
+        if !page_id.contains('/') {
+            return None;
+        }
+
+        Some(Route {
+            page_id,
+            fragment: fragment.filter(|fragment| !fragment.is_empty()),
+        })
+    }
+}
+
+/// Page files from the navigation mapped to their combined IDs, used to turn markdown links
+/// between files into site routes
+#[derive(Debug, Default)]
+pub struct PageRoutes {
+    files: HashMap<String, String>,
+}
+
+impl PageRoutes {
+    /// Register a page by its `file` path relative to the docs folder
+    pub fn add(&mut self, file: &str, combined_id: &str) {
+        if let Some(path) = normalize(file) {
+            self.files.insert(path, combined_id.to_string());
+        }
+    }
+
+    /// Resolve a relative `.md` link found in `from_file` (relative to the docs folder).
+    /// Returns `None` for links that are not to markdown files, and an error naming the
+    /// resolved path when the file is not in the navigation.
+    pub fn resolve(&self, from_file: &str, destination: &str) -> Option<Result<Route, String>> {
+        if destination.starts_with('#') || destination.starts_with("//") || destination.contains("://") || destination.starts_with("mailto:") {
+            return None;
+        }
+
+        let (path, fragment) = match destination.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (destination, None),
+        };
+        if !path.to_ascii_lowercase().ends_with(".md") {
+            return None;
+        }
+
+        // `/guide/setup.md` is relative to the docs folder, anything else to the linking file
+        let path = path.replace("%20", " ");
+        let joined = match path.strip_prefix('/') {
+            Some(path) => path.to_string(),
+            None => match from_file.rsplit_once('/') {
+                Some((dir, _)) => format!("{}/{}", dir, path),
+                None => path,
+            },
+        };
+
+        let Some(resolved) = normalize(&joined) else {
+            return Some(Err(format!("'{}' is outside the docs folder", destination)));
+        };
+
+        Some(match self.files.get(&resolved) {
+            Some(page_id) => Ok(Route {
+                page_id: page_id.clone(),
+                fragment: fragment.filter(|fragment| !fragment.is_empty()).map(str::to_string),
+            }),
+            None => Err(format!("{} is not in the navigation", resolved)),
+        })
+    }
+
+    /// Every file and its ID in a stable order, so that pages rendered against different
+    /// routes are not reused from the build cache
+    pub fn fingerprint(&self) -> String {
+        let mut files: Vec<String> = self.files.iter()
+            .map(|(file, page_id)| format!("{}={}", file, page_id))
+            .collect();
+        files.sort();
+        files.join("\n")
+    }
+}
+
+/// Collapse `.` and `..` segments, returning `None` if the path climbs above the docs folder
+fn normalize(path: &str) -> Option<String> {
+    let mut segments = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(page_id: &str, fragment: Option<&str>) -> Route {
+        Route { page_id: page_id.to_string(), fragment: fragment.map(str::to_string) }
+    }
+
+    #[test]
+    fn hash_routes_name_a_page_and_heading() {
+        assert_eq!(Route::from_hash("#guide/setup/install"), Some(route("guide/setup/install", None)));
+        assert_eq!(Route::from_hash("#guide/setup#options"), Some(route("guide/setup", Some("options"))));
+        assert_eq!(Route::from_hash("#guide/setup%23options"), Some(route("guide/setup", Some("options"))));
+        assert_eq!(Route::from_hash("#guide/setup/"), Some(route("guide/setup", None)));
+        assert_eq!(Route::from_hash("#guide/setup#"), Some(route("guide/setup", None)));
+        assert_eq!(Route::from_hash("#options"), None);
+        assert_eq!(Route::from_hash("guide/setup"), None);
+    }
+
+    #[test]
+    fn markdown_links_resolve_through_the_navigation() {
+        let mut routes = PageRoutes::default();
+        routes.add("guide/setup.md", "guide/setup");
+        routes.add("./api/index.md", "api/index");
+
+        assert_eq!(routes.resolve("guide/intro.md", "setup.md#install"), Some(Ok(route("guide/setup", Some("install")))));
+        assert_eq!(routes.resolve("api/index.md", "../guide/./setup.md"), Some(Ok(route("guide/setup", None))));
+        assert_eq!(routes.resolve("guide/intro.md", "/api/index.md"), Some(Ok(route("api/index", None))));
+        assert_eq!(routes.resolve("guide/intro.md", "unknown.md"), Some(Err("guide/unknown.md is not in the navigation".to_string())));
+        assert_eq!(routes.resolve("intro.md", "../outside.md"), Some(Err("'../outside.md' is outside the docs folder".to_string())));
+        // Other files, anchors and external pages are not routes
+        for destination in ["setup.html", "guide/", "#install", "https://example.com/setup.md"] {
+            assert_eq!(routes.resolve("guide/intro.md", destination), None, "{}", destination);
+        }
+    }
+
+    #[test]
+    fn fingerprints_do_not_depend_on_insertion_order() {
+        let (mut first, mut second) = (PageRoutes::default(), PageRoutes::default());
+        first.add("a.md", "s/a");
+        first.add("b.md", "s/b");
+        second.add("b.md", "s/b");
+        second.add("a.md", "s/a");
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        assert_eq!(first.fingerprint(), "a.md=s/a\nb.md=s/b");
+        second.add("b.md", "s/renamed");
+        assert_ne!(first.fingerprint(), second.fingerprint());
+    }
+}
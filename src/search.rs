@@ -0,0 +1,347 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+use crate::{assets, SiteFile};
+
+/// Folder in the output directory holding the index shards of multi-page sites
+pub const SEARCH_DIR: &str = "search";
+
+/// Terms whose postings share a shard. Shards are alphabetical ranges of terms.
+const TERMS_PER_SHARD: usize = 1000;
+/// Records whose text shares a shard, which keeps a query's snippets to a few downloads
+const RECORDS_PER_SHARD: usize = 50;
+
+/// Term frequency saturation of the BM25 ranking
+const K1: f64 = 1.2;
+/// How much a record's length dampens its term frequencies
+const B: f64 = 0.75;
+
+/// How many times a term in a page title counts, compared with one in the text
+const PAGE_TITLE_BOOST: usize = 5;
+const HEADING_BOOST: usize = 3;
+/// Front matter tags and description
+const METADATA_BOOST: usize = 2;
+
+/// Searchable text of one part of a page: the text before its first `##` heading, or one
+/// heading and the text up to the next heading
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchRecord {
+    /// ID of the heading the record starts at, or `None` for the top of the page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heading_title: Option<String>,
+    pub text: String,
+}
+
+/// A page as the search sees it
+pub struct SearchPage {
+    pub id: String,
+    pub title: String,
+    /// Title of the navigation section holding the page
+    pub section: String,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    pub records: Vec<SearchRecord>,
+}
+
+/// Inverted index over the records of every page, written as the `searchIndex` object:
+///
+/// - `pages`: `{id, title, section, description?}` for each page
+/// - `records`: `{page, heading?, heading_title?, text}`, `page` indexing `pages`
+/// - `terms`: each term mapped to `[record, weight]` pairs, best match first
+#[derive(Debug, Serialize)]
+pub struct SearchIndex {
+    pages: Vec<IndexedPage>,
+    records: Vec<IndexedRecord>,
+    terms: BTreeMap<String, Vec<(usize, u32)>>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexedPage {
+    id: String,
+    title: String,
+    section: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct IndexedRecord {
+    page: usize,
+    #[serde(flatten)]
+    record: SearchRecord,
+}
+
+impl SearchIndex {
+    pub fn build(pages: Vec<SearchPage>) -> Self {
+        let mut indexed_pages = Vec::new();
+        let mut records = Vec::new();
+        // Boosted term counts and the boosted length of every record
+        let mut record_terms: Vec<(HashMap<String, usize>, usize)> = Vec::new();
+
+        for (page_index, page) in pages.into_iter().enumerate() {
+            for (record_index, record) in page.records.into_iter().enumerate() {
+                let mut counts = HashMap::new();
+                let mut add = |text: &str, boost: usize| {
+                    for token in tokenize(text) {
+                        *counts.entry(token).or_insert(0) += boost;
+                    }
+                };
+
+                add(&record.text, 1);
+                if let Some(heading_title) = &record.heading_title {
+                    add(heading_title, HEADING_BOOST);
+                }
+                // Page metadata belongs to the top of the page
+                if record_index == 0 {
+                    add(&page.title, PAGE_TITLE_BOOST);
+                    for tag in &page.tags {
+                        add(tag, METADATA_BOOST);
+                    }
+                    if let Some(description) = &page.description {
+                        add(description, METADATA_BOOST);
+                    }
+                }
+
+                let length = counts.values().sum();
+                record_terms.push((counts, length));
+                records.push(IndexedRecord { page: page_index, record });
+            }
+
+            indexed_pages.push(IndexedPage {
+                id: page.id,
+                title: page.title,
+                section: page.section,
+                description: page.description,
+            });
+        }
+
+        let record_count = record_terms.len() as f64;
+        let average_length = (record_terms.iter().map(|(_, length)| *length).sum::<usize>() as f64 / record_count).max(1.0);
+
+        let mut postings: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        for (record, (counts, _)) in record_terms.iter().enumerate() {
+            for (term, count) in counts {
+                postings.entry(term.clone()).or_default().push((record, *count));
+            }
+        }
+
+        let terms = postings.into_iter()
+            .map(|(term, records)| {
+                let frequency = records.len() as f64;
+                let idf = (1.0 + (record_count - frequency + 0.5) / (frequency + 0.5)).ln();
+
+                let mut weighted: Vec<(usize, u32)> = records.into_iter()
+                    .map(|(record, count)| {
+                        let tf = count as f64;
+                        let length = record_terms[record].1 as f64;
+                        let score = idf * tf * (K1 + 1.0) / (tf + K1 * (1.0 - B + B * length / average_length));
+                        // Two decimals are plenty for ranking and keep the index small
+                        (record, (score * 100.0).round().max(1.0) as u32)
+                    })
+                    .collect();
+                weighted.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+                (term, weighted)
+            })
+            .collect();
+
+        SearchIndex { pages: indexed_pages, records, terms }
+    }
+
+    /// The index as the `searchIndex` constant read by the search in `javascript.rs`
+    pub fn to_javascript(&self) -> Result<String> {
+        Ok(format!("const searchIndex = {};\n", serde_json::to_string(self).map_err(serialization_error)?))
+    }
+}
+
+/// First file a sharded index is read from: the pages, every term in order (for prefix and
+/// typo matching) and the names of the shards holding the postings and records
+#[derive(Debug, Serialize)]
+struct Manifest<'a> {
+    pages: &'a [IndexedPage],
+    terms: Vec<&'a str>,
+    terms_per_shard: usize,
+    term_shards: Vec<String>,
+    records_per_shard: usize,
+    record_shards: Vec<String>,
+}
+
+impl SearchIndex {
+    /// Split the index into gzipped JSON shards, which the search fetches as queries need
+    /// them. The manifest comes first. Names carry a content hash, so unchanged shards keep
+    /// their names and stay cached by browsers.
+    pub fn shards(&self) -> Result<Vec<SiteFile>> {
+        let terms: Vec<&str> = self.terms.keys().map(String::as_str).collect();
+
+        let mut shards = Vec::new();
+        for (number, chunk) in terms.chunks(TERMS_PER_SHARD).enumerate() {
+            let postings: BTreeMap<&str, &Vec<(usize, u32)>> = chunk.iter()
+                .map(|term| (*term, &self.terms[*term]))
+                .collect();
+            shards.push(shard(&format!("terms-{}", number), &postings)?);
+        }
+        for (number, chunk) in self.records.chunks(RECORDS_PER_SHARD).enumerate() {
+            shards.push(shard(&format!("records-{}", number), &chunk)?);
+        }
+
+        let (term_shards, record_shards): (Vec<&SiteFile>, Vec<&SiteFile>) = shards.iter()
+            .partition(|shard| shard.path.starts_with(&format!("{}/terms-", SEARCH_DIR)));
+        let manifest = Manifest {
+            pages: &self.pages,
+            terms,
+            terms_per_shard: TERMS_PER_SHARD,
+            term_shards: term_shards.iter().map(|shard| shard.path.clone()).collect(),
+            records_per_shard: RECORDS_PER_SHARD,
+            record_shards: record_shards.iter().map(|shard| shard.path.clone()).collect(),
+        };
+
+        shards.insert(0, shard("index", &manifest)?);
+        Ok(shards)
+    }
+}
+
+/// A gzipped JSON file of a sharded index, e.g. `search/terms-0-3f9a2c1e.json.gz`
+fn shard<T: Serialize>(name: &str, value: &T) -> Result<SiteFile> {
+    let json = serde_json::to_string(value).map_err(serialization_error)?;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(json.as_bytes()).map_err(serialization_error)?;
+
+    Ok(SiteFile {
+        path: format!("{}/{}-{}.json.gz", SEARCH_DIR, name, assets::short_hash(json.as_bytes())),
+        content: encoder.finish().map_err(serialization_error)?,
+    })
+}
+
+fn serialization_error(error: impl std::fmt::Display) -> Error {
+    Error::SearchIndex { message: error.to_string() }
+}
+
+/// Shards of earlier builds found in `search_dir`, used to remove them
+pub fn shard_files(search_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(search_dir) else {
+        return Vec::new();
+    };
+    entries.flatten()
+        .filter(|entry| entry.file_name().to_str()
+            .and_then(|name| name.strip_suffix(".json.gz"))
+            .is_some_and(assets::is_hashed_name))
+        .map(|entry| entry.path())
+        .collect()
+}
+
+/// Lowercase words of at least two characters. `javascript.rs` splits queries the same way.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 2)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use crate::page::ParsedPage;
+
+    fn page(id: &str, text: &str) -> SearchPage {
+        SearchPage {
+            id: id.to_string(),
+            title: id.to_string(),
+            section: "Guide".to_string(),
+            description: None,
+            tags: Vec::new(),
+            records: vec![SearchRecord { heading: None, heading_title: None, text: text.to_string() }],
+        }
+    }
+
+    #[test]
+    fn dense_short_records_outrank_passing_mentions() {
+        let filler = "the build writes every page of the site with its styles and scripts ".repeat(20);
+        let index = SearchIndex::build(vec![
+            page("long", &format!("{} you could deploy it later {}", filler, filler)),
+            page("short", "Deploy the output folder; deploy again after each build."),
+            page("other", "Nothing related here."),
+        ]);
+
+        let postings = &index.terms["deploy"];
+        assert_eq!(postings.len(), 2);
+        assert_eq!(index.records[postings[0].0].page, 1);
+        assert!(postings[0].1 > postings[1].1);
+        assert!(!index.terms.contains_key("a"), "single characters are not indexed");
+    }
+
+    #[test]
+    fn shards_split_terms_and_records_into_stable_chunks() {
+        let pages = || (0..120)
+            .map(|n| page(&format!("page-{}", n), &(0..20).map(|i| format!("word{}x{}", n, i)).collect::<Vec<_>>().join(" ")))
+            .collect::<Vec<_>>();
+        let index = SearchIndex::build(pages());
+        assert!(index.terms.len() > 2 * TERMS_PER_SHARD && index.records.len() > 2 * RECORDS_PER_SHARD);
+
+        let shards = index.shards().unwrap();
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(&shards[0].content[..]).read_to_string(&mut json).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        let term_shards = manifest["term_shards"].as_array().unwrap();
+        let record_shards = manifest["record_shards"].as_array().unwrap();
+        assert_eq!(term_shards.len(), index.terms.len().div_ceil(TERMS_PER_SHARD));
+        assert_eq!(record_shards.len(), index.records.len().div_ceil(RECORDS_PER_SHARD));
+        assert_eq!(shards.len(), 1 + term_shards.len() + record_shards.len());
+        assert!(shards[0].path.starts_with("search/index-"));
+        assert!(term_shards[0].as_str().unwrap().starts_with("search/terms-0-"));
+        assert!(record_shards[2].as_str().unwrap().starts_with("search/records-2-"));
+
+        let terms: Vec<&str> = manifest["terms"].as_array().unwrap().iter().map(|term| term.as_str().unwrap()).collect();
+        assert_eq!(terms.len(), index.terms.len());
+        assert!(terms.windows(2).all(|pair| pair[0] < pair[1]), "terms are sorted");
+
+        let paths = |shards: Vec<SiteFile>| shards.into_iter().map(|shard| shard.path).collect::<Vec<_>>();
+        assert_eq!(paths(shards), paths(SearchIndex::build(pages()).shards().unwrap()));
+    }
+
+    #[test]
+    fn records_hold_plain_text_split_at_headings() {
+        let markdown = "\
+# Setup
+
+Install **GlowDoc** from [the releases](https://example.com/releases).
+
+## Build it
+
+```bash
+cargo build --release
+```
+
+### Check `links`
+
+Run *check* first.
+";
+        let page = ParsedPage::from_source(Path::new("docs"), "setup.md", markdown).unwrap();
+        let records = page.search_records();
+
+        assert_eq!(records, [
+            SearchRecord { heading: None, heading_title: None, text: "Setup\nInstall GlowDoc from the releases.".to_string() },
+            SearchRecord {
+                heading: Some("build-it".to_string()),
+                heading_title: Some("Build it".to_string()),
+                text: "cargo build --release".to_string(),
+            },
+            SearchRecord {
+                heading: Some("check-links".to_string()),
+                heading_title: Some("Check links".to_string()),
+                text: "Run check first.".to_string(),
+            },
+        ]);
+        for record in &records {
+            for syntax in ["**", "https", "example.com", "```", "bash", "`", "*"] {
+                assert!(!record.text.contains(syntax), "{:?} in {:?}", syntax, record.text);
+            }
+        }
+    }
+}
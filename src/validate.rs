@@ -0,0 +1,361 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use crate::{css, ignore, Config, NavigationItem};
+
+/// A problem with `config.yaml`, pointing at the value it concerns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line and column, or `None` when the value could not be located
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The site is built anyway, e.g. a page whose file is missing shows an error message
+    Warning,
+    /// The site cannot be built correctly, e.g. two pages would share an ID
+    Error,
+}
+
+impl Diagnostic {
+    /// The diagnostic as `config.yaml:12:15: message`
+    pub fn display<'a>(&'a self, config_path: &'a str) -> impl fmt::Display + 'a {
+        DisplayDiagnostic { diagnostic: self, config_path }
+    }
+}
+
+struct DisplayDiagnostic<'a> {
+    diagnostic: &'a Diagnostic,
+    config_path: &'a str,
+}
+
+impl fmt::Display for DisplayDiagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.diagnostic.position {
+            Some((line, column)) => write!(f, "{}:{}:{}: {}", self.config_path, line, column, self.diagnostic.message),
+            None => write!(f, "{}: {}", self.config_path, self.diagnostic.message),
+        }
+    }
+}
+
+/// Check `config`, parsed from `source`, against the docs folder: page files exist, section
+/// and item IDs are valid URL fragments and unique among their siblings, the theme exists and
+/// the `exclude` globs are valid
+pub fn validate(config: &Config, source: &str, docs_path: &Path) -> Vec<Diagnostic> {
+    let positions = locate(source);
+    let mut diagnostics = Vec::new();
+    let mut report = |severity: Severity, path: &str, message: String| {
+        diagnostics.push(Diagnostic { severity, position: position(&positions, path), message });
+    };
+
+    if !css::THEMES.contains(&config.theme.as_str()) {
+        report(Severity::Warning, "theme", format!(
+            "unknown theme '{}', the default theme is used (available: {})",
+            config.theme, css::THEMES.join(", ")
+        ));
+    }
+
+    for (index, pattern) in config.exclude.iter().enumerate() {
+        if let Some(message) = ignore::invalid_pattern(pattern) {
+            report(Severity::Error, &format!("exclude[{}]", index), message);
+        }
+    }
+
+    let mut section_ids = HashSet::new();
+    for (index, section) in config.navigation.iter().enumerate() {
+        // An `auto` entry before the section takes up an index of the YAML list
+        let index = index + usize::from(config.navigation.auto.is_some_and(|auto| auto <= index));
+        let path = format!("navigation[{}]", index);
+        check_id(&section.id, &format!("{}.id", path), "among the sections", &mut section_ids, &mut report);
+        check_items(&section.items, &format!("{}.items", path), &section.id, docs_path, &mut report);
+    }
+
+    diagnostics
+}
+
+fn check_items(items: &[NavigationItem], path: &str, combined_id: &str, docs_path: &Path, report: &mut impl FnMut(Severity, &str, String)) {
+    let mut ids = HashSet::new();
+    for (index, item) in items.iter().enumerate() {
+        let path = format!("{}[{}]", path, index);
+        check_id(&item.id, &format!("{}.id", path), &format!("in '{}'", combined_id), &mut ids, report);
+
+        if let Some(file) = &item.file {
+            if !docs_path.join(file).is_file() {
+                report(Severity::Warning, &format!("{}.file", path), format!(
+                    "page file '{}' not found in {}", file, docs_path.display()
+                ));
+            }
+        }
+
+        check_items(&item.items, &format!("{}.items", path), &format!("{}/{}", combined_id, item.id), docs_path, report);
+    }
+}
+
+/// Report an ID that cannot be used in `#section/page` routes and file names, or that a
+/// sibling already uses. `siblings` says where, e.g. `in 'guide'`.
+fn check_id(id: &str, path: &str, siblings: &str, seen: &mut HashSet<String>, report: &mut impl FnMut(Severity, &str, String)) {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        report(Severity::Error, path, format!(
+            "ID '{}' is not a valid URL fragment; use letters, digits, '-' and '_'", id
+        ));
+    } else if !seen.insert(id.to_string()) {
+        report(Severity::Error, path, format!("duplicate ID '{}' {}", id, siblings));
+    }
+}
+
+/// Position of `path`, or of the closest enclosing value that was located
+fn position(positions: &HashMap<String, (usize, usize)>, path: &str) -> Option<(usize, usize)> {
+    let mut path = path;
+    loop {
+        if let Some(position) = positions.get(path) {
+            return Some(*position);
+        }
+        path = &path[..path.rfind(['.', '['])?];
+    }
+}
+
+/// 1-based line and column of every value in block-style YAML, keyed by paths such as
+/// `navigation[0].items[1].file`. Nested collections are located at their key; flow-style
+/// collections (`[a, b]`, `{a: b}`) are not looked into.
+fn locate(source: &str) -> HashMap<String, (usize, usize)> {
+    /// A block mapping or sequence whose keys or dashes start at `indent`
+    struct Block {
+        indent: usize,
+        path: String,
+        /// Items seen so far, for sequences
+        items: Option<usize>,
+    }
+
+    let mut positions = HashMap::new();
+    let mut blocks = vec![Block { indent: 0, path: String::new(), items: None }];
+    // Key whose value is on the following lines, with the indentation of the key
+    let mut open: Option<(String, usize)> = None;
+    // Indentation of a key with a `|` or `>` value, whose lines are skipped
+    let mut block_scalar: Option<usize> = None;
+
+    for (number, line) in source.lines().enumerate() {
+        let content = line.trim_start();
+        let mut indent = line.len() - content.len();
+        if content.is_empty() || content.starts_with('#') || content == "---" {
+            continue;
+        }
+        if let Some(key_indent) = block_scalar {
+            if indent > key_indent {
+                continue;
+            }
+            block_scalar = None;
+        }
+
+        while blocks.len() > 1 && blocks.last().unwrap().indent > indent {
+            blocks.pop();
+        }
+        let is_item = content == "-" || content.starts_with("- ");
+        if let Some((path, key_indent)) = open.take() {
+            if indent > key_indent || (indent == key_indent && is_item) {
+                blocks.push(Block { indent, path, items: is_item.then_some(0) });
+            }
+        }
+
+        let mut content = content;
+        if is_item {
+            let Some(block) = blocks.last_mut().filter(|block| block.indent == indent) else {
+                continue;
+            };
+            let Some(items) = block.items.as_mut() else {
+                continue;
+            };
+            let path = format!("{}[{}]", block.path, items);
+            *items += 1;
+
+            let rest = content[1..].trim_start();
+            let rest_indent = indent + (content.len() - rest.len());
+            if rest.is_empty() {
+                positions.insert(path.clone(), (number + 1, indent + 1));
+                open = Some((path, indent));
+                continue;
+            }
+            if split_key(rest).is_none() {
+                positions.insert(path, (number + 1, rest_indent + 1));
+                continue;
+            }
+            // `- key: value` starts a mapping whose keys line up with `key`
+            positions.insert(path.clone(), (number + 1, rest_indent + 1));
+            blocks.push(Block { indent: rest_indent, path, items: None });
+            content = rest;
+            indent = rest_indent;
+        }
+
+        let Some((key, value)) = split_key(content) else {
+            continue;
+        };
+        // A key lined up with the dashes of a sequence ends it, as in `items:\n- a\nnext: b`
+        while blocks.len() > 1 && blocks.last().is_some_and(|block| block.indent == indent && block.items.is_some()) {
+            blocks.pop();
+        }
+        let Some(block) = blocks.last().filter(|block| block.indent == indent && block.items.is_none()) else {
+            continue;
+        };
+        let path = if block.path.is_empty() { key.to_string() } else { format!("{}.{}", block.path, key) };
+
+        let value_column = indent + (content.len() - value.len()) + 1;
+        match value.split(" #").next().unwrap_or("").trim() {
+            "" => {
+                positions.insert(path.clone(), (number + 1, indent + 1));
+                open = Some((path, indent));
+            }
+            scalar if scalar.starts_with('|') || scalar.starts_with('>') => {
+                positions.insert(path, (number + 1, value_column));
+                block_scalar = Some(indent);
+            }
+            _ => {
+                positions.insert(path, (number + 1, value_column));
+            }
+        }
+    }
+
+    positions
+}
+
+/// Split `key: value` into the unquoted key and the value text, which is empty when the value
+/// is on the following lines
+fn split_key(content: &str) -> Option<(&str, &str)> {
+    let (key, value) = match content.find(": ") {
+        Some(index) => (&content[..index], &content[index + 2..]),
+        None => (content.strip_suffix(':')?, ""),
+    };
+    let key = key.trim();
+    if key.is_empty() || key.starts_with(['[', '{', '"', '\'']) && !key.ends_with(['"', '\'']) {
+        return None;
+    }
+    Some((key.trim_matches(['"', '\'']), value.trim_start()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn locates_values_in_both_sequence_styles() {
+        let source = "\
+title: T  # comment
+description: |
+  id: not a key
+navigation:
+- title: S
+  id: s
+  items:
+  - title: P
+    file: s/p.md
+  extra: true
+- title: S2
+  items:
+      - id: q
+";
+        let positions = locate(source);
+        assert_eq!(positions["title"], (1, 8));
+        assert_eq!(positions.get("description.id"), None);
+        assert_eq!(positions["navigation[0].id"], (6, 7));
+        assert_eq!(positions["navigation[0].items[0].file"], (9, 11));
+        assert_eq!(positions["navigation[0].extra"], (10, 10));
+        assert_eq!(positions["navigation[1].items[0].id"], (13, 13));
+    }
+    
+    #[test]
+    fn falls_back_to_the_enclosing_value() {
+        let positions = locate("navigation:\n  - {title: S, id: s}\n");
+        assert_eq!(position(&positions, "navigation[0].id"), Some((2, 5)));
+        assert_eq!(position(&positions, "theme"), None);
+    }
+    
+    /// Diagnostics for `source`, with the docs folder holding only `guide/intro.md`
+    fn diagnostics(name: &str, source: &str) -> Vec<Diagnostic> {
+        let docs = std::env::temp_dir().join(format!("glowdoc-validate-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(docs.join("guide")).unwrap();
+        std::fs::write(docs.join("guide/intro.md"), "# Intro\n").unwrap();
+        let config: Config = serde_yaml::from_str(source).unwrap();
+        let diagnostics = validate(&config, source, &docs);
+        std::fs::remove_dir_all(&docs).unwrap();
+        diagnostics
+    }
+    
+    fn error(line: usize, column: usize, message: &str) -> Diagnostic {
+        Diagnostic { severity: Severity::Error, position: Some((line, column)), message: message.to_string() }
+    }
+    
+    #[test]
+    fn missing_page_files_are_warnings() {
+        let source = "\
+title: T
+description: D
+navigation:
+  - title: Guide
+    id: guide
+    items:
+      - title: Intro
+        id: intro
+        file: guide/intro.md
+      - title: Gone
+        id: gone
+        file: guide/gone.md
+";
+        let found = diagnostics("missing", source);
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].severity, found[0].position), (Severity::Warning, Some((12, 15))));
+        assert!(found[0].message.starts_with("page file 'guide/gone.md' not found in "), "{}", found[0].message);
+    }
+    
+    #[test]
+    fn ids_must_be_unique_among_siblings_and_valid_fragments() {
+        let source = "\
+title: T
+description: D
+navigation:
+  - title: Guide
+    id: guide
+    items:
+      - title: Intro
+        id: intro
+        file: guide/intro.md
+      - title: Again
+        id: intro
+        file: guide/intro.md
+      - title: Spaced
+        id: getting started
+  - title: Other
+    id: guide
+    items:
+      - title: Intro
+        id: intro
+        file: guide/intro.md
+";
+        assert_eq!(diagnostics("ids", source), [
+            error(11, 13, "duplicate ID 'intro' in 'guide'"),
+            error(14, 13, "ID 'getting started' is not a valid URL fragment; use letters, digits, '-' and '_'"),
+            error(16, 9, "duplicate ID 'guide' among the sections"),
+        ]);
+    }
+    
+    #[test]
+    fn unknown_themes_are_warnings() {
+        let source = "title: T\ndescription: D\ntheme: neon\nnavigation: []\n";
+        assert_eq!(diagnostics("theme", source), [Diagnostic {
+            severity: Severity::Warning,
+            position: Some((3, 8)),
+            message: "unknown theme 'neon', the default theme is used (available: default, purple, vibrant)".to_string(),
+        }]);
+    }
+    
+    #[test]
+    fn invalid_exclude_globs_are_errors() {
+        let source = "title: T\ndescription: D\nnavigation: []\nexclude:\n  - drafts/\n  - '[z-a].md'\n";
+        let config: Config = serde_yaml::from_str(source).unwrap();
+        let diagnostics = validate(&config, source, Path::new("docs"));
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].position, Some((6, 5)));
+        assert!(diagnostics[0].message.starts_with("invalid pattern '[z-a].md'"), "{}", diagnostics[0].message);
+    }
+}
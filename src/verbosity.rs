@@ -0,0 +1,26 @@
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much progress the builder prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Verbose logging is enabled by `--verbose` or the `GLOWDOC_DEBUG` environment variable
+pub fn is_verbose() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) == Verbosity::Verbose as u8 || env::var("GLOWDOC_DEBUG").is_ok()
+}
+
+/// `--quiet` silences progress output; errors are always printed
+pub fn is_quiet() -> bool {
+    VERBOSITY.load(Ordering::Relaxed) == Verbosity::Quiet as u8
+}